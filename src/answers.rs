@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{AocError, ParseError};
+use crate::parse;
+use crate::solver::{Answer, Part};
+
+pub const ANSWERS_DIR: &str = "answers";
+
+// the known answers of one input, read from lines like "one: 142";
+// a part without a line has no known answer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub one: Option<Answer>,
+    pub two: Option<Answer>,
+}
+
+impl Expected {
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.one.as_ref(),
+            Part::Two => self.two.as_ref(),
+        }
+    }
+}
+
+impl FromStr for Expected {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut expected = Expected::default();
+        for line in parse::lines(s) {
+            if line.text.trim().is_empty() {
+                continue;
+            }
+            let (key, answer) = line.split_once(line.text, ":")?;
+            let part = match key.trim() {
+                "one" => &mut expected.one,
+                "two" => &mut expected.two,
+                _ => return Err(line.error(key, "expected one or two")),
+            };
+            if part.is_some() {
+                return Err(line.error(key, "duplicate answer"));
+            }
+            *part = Some(answer.trim().parse().unwrap());
+        }
+        Ok(expected)
+    }
+}
+
+// a missing file means that no answer is known for the input
+pub fn try_read_answers(
+    dir: &Path,
+    day: u8,
+    name: &str,
+) -> Result<Expected, AocError> {
+    match crate::try_read_from(dir, day, name) {
+        Ok(text) => text.parse().map_err(|e: ParseError| e.into_error(day)),
+        Err(AocError::MissingFile { .. }) => Ok(Expected::default()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let expected: Expected =
+            "one: 57346\n\ntwo: fifty stars\n".parse().unwrap();
+        assert_eq!(expected.get(Part::One), Some(&Answer::Unsigned(57346)));
+        assert_eq!(expected.get(Part::Two), Some(&"fifty stars".into()));
+
+        let expected: Expected = "one: 32000000\n".parse().unwrap();
+        assert_eq!(expected.get(Part::Two), None);
+
+        let err = "one: 1\nthree: 3\n".parse::<Expected>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!("one: 1\none: 2\n".parse::<Expected>().is_err());
+        assert!("42\n".parse::<Expected>().is_err());
+    }
+
+    #[test]
+    fn read_answers() {
+        let dir = Path::new(ANSWERS_DIR);
+        let expected = try_read_answers(dir, 1, "input").unwrap();
+        assert_eq!(expected.get(Part::One), Some(&57346u32.into()));
+        let expected = try_read_answers(dir, 1, "no-such-file").unwrap();
+        assert_eq!(expected, Expected::default());
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// runs `f` `warmup` times without measuring it, then `runs` times with a
+// monotonic clock; stops at the first error
+pub fn sample<T, E>(
+    warmup: usize,
+    runs: usize,
+    mut f: impl FnMut() -> Result<T, E>,
+) -> Result<Vec<Duration>, E> {
+    for _ in 0..warmup {
+        f()?;
+    }
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let t0 = Instant::now();
+        f()?;
+        samples.push(t0.elapsed());
+    }
+    Ok(samples)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = match n % 2 {
+            1 => sorted[n / 2],
+            _ => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        };
+        let nanos: Vec<f64> =
+            sorted.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        // the sample standard deviation, zero for a single run
+        let variance = match n {
+            1 => 0.0,
+            _ => {
+                nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>()
+                    / (n - 1) as f64
+            }
+        };
+        Some(Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+
+    // the same statistics with `offset` taken off every sample
+    pub fn minus(&self, offset: Duration) -> Self {
+        Stats {
+            runs: self.runs,
+            min: self.min.saturating_sub(offset),
+            median: self.median.saturating_sub(offset),
+            mean: self.mean.saturating_sub(offset),
+            stddev: self.stddev,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>12}  {:>12}  {:>12}  {:>12}",
+            format!("{:.1?}", self.min),
+            format!("{:.1?}", self.median),
+            format!("{:.1?}", self.mean),
+            format!("{:.1?}", self.stddev),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        let stats = Stats::new(&samples).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev, Duration::from_nanos(1290994));
+
+        let stats = stats.minus(Duration::from_millis(2));
+        assert_eq!(stats.min, Duration::ZERO);
+        assert_eq!(stats.median, Duration::from_micros(500));
+
+        let stats = Stats::new(&samples[..1]).unwrap();
+        assert_eq!(
+            (stats.median, stats.stddev),
+            (samples[0], Duration::ZERO)
+        );
+        assert!(Stats::new(&[]).is_none());
+    }
+
+    #[test]
+    fn samples() {
+        let mut calls = 0;
+        let samples = sample(2, 3, || {
+            calls += 1;
+            Ok::<_, ()>(())
+        });
+        assert_eq!(samples.unwrap().len(), 3);
+        assert_eq!(calls, 5);
+        assert_eq!(sample(1, 3, || Err::<(), _>("bad")), Err("bad"));
+    }
+}
@@ -0,0 +1,618 @@
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::answers::{self, Expected};
+use crate::error::AocError;
+use crate::params::{self, Param};
+use crate::report::Format;
+use crate::solver::Part;
+use crate::visualize;
+
+pub const USAGE: &str = "\
+Usage: aoc [COMMAND] [OPTIONS] [DAYS]...
+
+Commands:
+  run     solve the selected days (default)
+  list    list the available days and their params
+  bench   solve each part of the selected days repeatedly and report
+          min/median/mean/stddev of the parse and solve durations
+  check   compare the answers of the selected days with the expected
+          answers in answers/NN-input.txt (or NN-example.txt, ...)
+  fetch   download the inputs of the selected days that are not in the
+          input directory yet, with the session token of $AOC_SESSION
+          or of `session = ...` in ~/.config/aoc/config ($AOC_CONFIG)
+  generate
+          print a random input of a single day, the same for the same
+          --size and --seed
+  crosscheck
+          compare the answers of the selected days that have a brute
+          force reference (6, 8, 20 and 21) with the reference, on
+          --runs generated inputs or on the --example or --file input
+
+Days:
+  a day between 1 and 25, a range like 1-5 or a list like 1-5,9;
+  all days are selected when none are given
+
+Options:
+  -p, --part <one|two>     solve only the given part (or 1|2)
+  -e, --example[=N]        use inputs/NN-example.txt (or NN-example-N.txt)
+                           instead of the input, solving only the parts
+                           the example has answers for
+  -t, --time               report the duration of each part
+  -i, --input-dir <DIR>    read the inputs from DIR instead of inputs/,
+                           defaults to $AOC_INPUT_DIR when it is set
+  -f, --file <PATH>        read the input of a single day from PATH,
+                           `-` reads it from stdin
+  -a, --answers-dir <DIR>  read the expected answers from DIR instead of
+                           answers/
+  -j, --jobs <N>           solve up to N parts at the same time (default 1,
+                           0 for one per CPU), the results are still
+                           printed in order
+  -n, --runs <N>           measured runs per part for bench, or inputs
+                           per day for crosscheck (default 10)
+  -w, --warmup <N>         unmeasured runs per part for bench (default 3)
+  -P, --param <KEY=VALUE>  change a constant of the puzzle, like
+                           `-P cycles=1000` for day 14, `aoc list` shows
+                           the params of every day; it only goes to the
+                           days with the key, or to one day as
+                           `-P 14.cycles=1000`
+      --format <FORMAT>    print the results as text (default), json
+                           or csv, one record per day and part
+      --visualize[=FMT]    draw the selected days that can be drawn
+                           instead of solving them, as ansi (default) on
+                           the terminal, or as ppm or svg images in
+                           visualizations/NN-input.ppm (or .svg)
+      --size <N>           the size of a generated input, what it counts
+                           depends on the day and is printed to stderr
+      --seed <N>           the seed of a generated input, defaults to a
+                           random one that is printed to stderr; the
+                           inputs of crosscheck use the seeds from it on
+      --profile            time the named spans of the parts that have
+                           them (parse, compress_map, dfs, ...) and print
+                           them after the answers
+      --url <URL>          the site fetch downloads from, defaults to
+                           $AOC_BASE_URL or `base_url = ...` in the config
+                           file; only plain http is supported
+  -h, --help               print this help
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(Options),
+    List,
+    Bench(Options),
+    Check(Options),
+    Fetch(Options),
+    Generate(Options),
+    Crosscheck(Options),
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub days: Vec<u8>,
+    pub part: Option<Part>,
+    // the example to use instead of the input, starting at 1
+    pub example: Option<usize>,
+    pub time: bool,
+    pub input_dir: Option<PathBuf>,
+    pub file: Option<PathBuf>,
+    pub answers_dir: Option<PathBuf>,
+    pub jobs: usize,
+    pub runs: usize,
+    pub warmup: usize,
+    pub format: Format,
+    pub base_url: Option<String>,
+    pub visualize: Option<visualize::Format>,
+    pub params: Vec<Param>,
+    pub size: Option<usize>,
+    pub seed: Option<u64>,
+    pub profile: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            days: (1..=25).collect(),
+            part: None,
+            example: None,
+            time: false,
+            input_dir: None,
+            file: None,
+            answers_dir: None,
+            jobs: 1,
+            runs: 10,
+            warmup: 3,
+            format: Format::Text,
+            base_url: None,
+            visualize: None,
+            params: vec![],
+            size: None,
+            seed: None,
+            profile: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+
+    // an example applies to the parts it has answers for, or to both
+    // parts when it has none
+    pub fn parts_for(&self, day: u8) -> Vec<Part> {
+        if self.part.is_some() || self.example.is_none() {
+            return self.parts();
+        }
+        let answers = self.read_answers(day).unwrap_or_default();
+        let parts: Vec<Part> = Part::ALL
+            .into_iter()
+            .filter(|&part| answers.get(part).is_some())
+            .collect();
+        match parts.is_empty() {
+            true => self.parts(),
+            false => parts,
+        }
+    }
+
+    // The params of a day: `DAY.key=value` only goes to that day, and
+    // with several days a plain `key=value` only to the days that have
+    // the key, unless none of them has it and it is an error for all.
+    pub fn params_for(&self, day: u8) -> Vec<Param> {
+        let declares = |day: u8, key: &str| {
+            crate::solver(day).is_some_and(|s| s.params().contains(&key))
+        };
+        let mut params = vec![];
+        for (key, value) in self.params.iter() {
+            let scoped = key
+                .split_once('.')
+                .and_then(|(d, key)| Some((d.parse::<u8>().ok()?, key)));
+            match scoped {
+                Some((d, key)) if d == day => {
+                    params.push((key.to_string(), value.clone()))
+                }
+                Some(_) => (),
+                None if self.days.len() == 1
+                    || declares(day, key)
+                    || !self.days.iter().any(|&d| declares(d, key)) =>
+                {
+                    params.push((key.clone(), value.clone()))
+                }
+                None => (),
+            }
+        }
+        params
+    }
+
+    // "input", "example" or "example-N"
+    pub fn input_name(&self) -> String {
+        match self.example {
+            Some(n) => crate::example_name(n),
+            None => "input".to_string(),
+        }
+    }
+
+    pub fn input_dir(&self) -> PathBuf {
+        match &self.input_dir {
+            Some(dir) => dir.clone(),
+            None => crate::input_dir(),
+        }
+    }
+
+    pub fn read_input(&self, day: u8) -> Result<String, AocError> {
+        match self.file.as_deref() {
+            Some(path) if path == Path::new("-") => crate::try_read_stdin(),
+            Some(path) => crate::try_read_file(path),
+            None => crate::try_read_from(
+                &self.input_dir(),
+                day,
+                &self.input_name(),
+            ),
+        }
+    }
+
+    // no answers are known for an input given with --file
+    pub fn read_answers(&self, day: u8) -> Result<Expected, AocError> {
+        if self.file.is_some() {
+            return Ok(Expected::default());
+        }
+        let dir = match &self.answers_dir {
+            Some(dir) => dir.clone(),
+            None => PathBuf::from(answers::ANSWERS_DIR),
+        };
+        answers::try_read_answers(&dir, day, &self.input_name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidDay(String),
+    InvalidPart(String),
+    InvalidFormat(String),
+    InvalidCount(String),
+    InvalidVisualization(String),
+    InvalidParam(String),
+    SingleDayRequired,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::UnknownFlag(flag) => {
+                write!(f, "unknown option {}", flag)
+            }
+            CliError::MissingValue(flag) => {
+                write!(f, "{} requires a value", flag)
+            }
+            CliError::InvalidDay(day) => {
+                write!(f, "invalid day {:?}, expected 1..=25", day)
+            }
+            CliError::InvalidPart(part) => {
+                write!(f, "invalid part {:?}, expected one or two", part)
+            }
+            CliError::InvalidFormat(format) => write!(
+                f,
+                "invalid format {:?}, expected text, json or csv",
+                format
+            ),
+            CliError::InvalidCount(count) => {
+                write!(f, "invalid count {:?}, expected a number", count)
+            }
+            CliError::InvalidVisualization(format) => write!(
+                f,
+                "invalid visualization {:?}, expected ansi, ppm or svg",
+                format
+            ),
+            CliError::InvalidParam(param) => {
+                write!(f, "invalid param {:?}, expected KEY=VALUE", param)
+            }
+            CliError::SingleDayRequired => {
+                write!(
+                    f,
+                    "a single day must be given with --file or to generate"
+                )
+            }
+        }
+    }
+}
+
+impl Error for CliError {}
+
+// "1-5,9" => [1, 2, 3, 4, 5, 9]
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, CliError> {
+    let day = |s: &str| match s.trim().parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(CliError::InvalidDay(s.to_string())),
+    };
+    let mut days = vec![];
+    for item in spec.split(',') {
+        match item.split_once('-') {
+            Some((a, b)) => {
+                let (a, b) = (day(a)?, day(b)?);
+                if a > b {
+                    return Err(CliError::InvalidDay(item.to_string()));
+                }
+                days.extend(a..=b);
+            }
+            None => days.push(day(item)?),
+        }
+    }
+    Ok(days)
+}
+
+fn count(s: String, min: usize) -> Result<usize, CliError> {
+    match s.parse() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(CliError::InvalidCount(s)),
+    }
+}
+
+pub fn parse_args<I>(args: I) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+    let command = match args.peek().map(|s| s.as_str()) {
+        Some(
+            "run" | "list" | "bench" | "check" | "fetch" | "generate"
+            | "crosscheck" | "help",
+        ) => args.next(),
+        _ => None,
+    };
+
+    let mut options = Options::default();
+    let mut days: Vec<u8> = vec![];
+    while let Some(arg) = args.next() {
+        let mut value =
+            || args.next().ok_or(CliError::MissingValue(arg.clone()));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--part" => {
+                let part = value()?;
+                options.part = Some(
+                    part.parse().map_err(|_| CliError::InvalidPart(part))?,
+                );
+            }
+            "-e" | "--example" => options.example = Some(1),
+            _ if arg.starts_with("--example=") => {
+                let n = &arg["--example=".len()..];
+                options.example = Some(count(n.to_string(), 1)?);
+            }
+            // -eN, any other flag that starts with -e is unknown
+            _ if arg.len() > 2
+                && arg.starts_with("-e")
+                && arg[2..].bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                options.example = Some(count(arg[2..].to_string(), 1)?);
+            }
+            "-t" | "--time" => options.time = true,
+            "-i" | "--input-dir" => options.input_dir = Some(value()?.into()),
+            "-f" | "--file" => options.file = Some(value()?.into()),
+            "-a" | "--answers-dir" => {
+                options.answers_dir = Some(value()?.into())
+            }
+            "-j" | "--jobs" => {
+                options.jobs = match count(value()?, 0)? {
+                    0 => {
+                        thread::available_parallelism().map_or(1, |n| n.get())
+                    }
+                    n => n,
+                }
+            }
+            "-n" | "--runs" => options.runs = count(value()?, 1)?,
+            "-w" | "--warmup" => options.warmup = count(value()?, 0)?,
+            "--format" => {
+                let format = value()?;
+                options.format = format
+                    .parse()
+                    .map_err(|_| CliError::InvalidFormat(format))?;
+            }
+            "-P" | "--param" => {
+                let param = value()?;
+                options.params.push(
+                    params::parse_param(&param)
+                        .ok_or(CliError::InvalidParam(param))?,
+                );
+            }
+            "--size" => options.size = Some(count(value()?, 1)?),
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(
+                    seed.parse().map_err(|_| CliError::InvalidCount(seed))?,
+                );
+            }
+            "--profile" => options.profile = true,
+            "--url" => options.base_url = Some(value()?),
+            "--visualize" => options.visualize = Some(Default::default()),
+            _ if arg.starts_with("--visualize=") => {
+                let format = &arg["--visualize=".len()..];
+                options.visualize = Some(format.parse().map_err(|_| {
+                    CliError::InvalidVisualization(format.to_string())
+                })?);
+            }
+            "-" => options.file = Some(arg.into()),
+            _ if arg.starts_with('-') => {
+                return Err(CliError::UnknownFlag(arg))
+            }
+            _ => {
+                for day in parse_days(&arg)? {
+                    if !days.contains(&day) {
+                        days.push(day);
+                    }
+                }
+            }
+        }
+    }
+    if !days.is_empty() {
+        options.days = days;
+    }
+    let generate = command.as_deref() == Some("generate");
+    if (options.file.is_some() || generate) && options.days.len() != 1 {
+        return Err(CliError::SingleDayRequired);
+    }
+
+    match command.as_deref() {
+        None | Some("run") => Ok(Command::Run(options)),
+        Some("list") => Ok(Command::List),
+        Some("bench") => Ok(Command::Bench(options)),
+        Some("check") => Ok(Command::Check(options)),
+        Some("fetch") => Ok(Command::Fetch(options)),
+        Some("generate") => Ok(Command::Generate(options)),
+        Some("crosscheck") => Ok(Command::Crosscheck(options)),
+        _ => Ok(Command::Help),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(parse_days("1-5,9"), Ok(vec![1, 2, 3, 4, 5, 9]));
+        assert_eq!(parse_days("25"), Ok(vec![25]));
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("5-1").is_err());
+        assert!(parse_days("1,,2").is_err());
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse(""), Ok(Command::Run(Options::default())));
+        assert_eq!(parse("list"), Ok(Command::List));
+        assert_eq!(parse("run --help"), Ok(Command::Help));
+
+        let options = Options {
+            days: vec![3, 1, 2],
+            part: Some(Part::Two),
+            example: Some(1),
+            ..Options::default()
+        };
+        assert_eq!(
+            parse("check 3 1-3 --part two -e"),
+            Ok(Command::Check(options))
+        );
+
+        let Ok(Command::Run(options)) = parse("--time 5 -") else {
+            panic!()
+        };
+        assert_eq!(options.days, [5]);
+        assert_eq!(options.file, Some(PathBuf::from("-")));
+        assert!(options.time);
+        assert!(!options.profile);
+
+        let Ok(Command::Run(options)) = parse("23 --profile") else {
+            panic!()
+        };
+        assert!(options.profile);
+
+        let Ok(Command::Run(options)) = parse("1 --format json") else {
+            panic!()
+        };
+        assert_eq!(options.format, Format::Json);
+
+        let Ok(Command::Fetch(options)) = parse("fetch 7 --url http://x")
+        else {
+            panic!()
+        };
+        assert_eq!(options.days, [7]);
+        assert_eq!(options.base_url.as_deref(), Some("http://x"));
+
+        let Ok(Command::Run(options)) = parse("10 --visualize=svg") else {
+            panic!()
+        };
+        assert_eq!(options.visualize, Some(visualize::Format::Svg));
+        assert_eq!(
+            parse("--visualize=png"),
+            Err(CliError::InvalidVisualization("png".to_string()))
+        );
+
+        let Ok(Command::Run(options)) = parse("14 -P cycles=3 --param a=")
+        else {
+            panic!()
+        };
+        let params =
+            [("cycles", "3"), ("a", "")].map(|(k, v)| (k.into(), v.into()));
+        assert_eq!(options.params, params);
+
+        let Ok(Command::Run(options)) =
+            parse("1-25 -P cycles=3 -P 11.expansion_one=5 -P nope=1")
+        else {
+            panic!()
+        };
+        let keys = |day| -> Vec<String> {
+            options
+                .params_for(day)
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect()
+        };
+        assert_eq!(keys(14), ["cycles", "nope"]);
+        assert_eq!(keys(11), ["expansion_one", "nope"]);
+        assert_eq!(keys(1), ["nope"]);
+        let Ok(Command::Run(options)) = parse("1 -P cycles=3") else {
+            panic!()
+        };
+        assert_eq!(options.params_for(1).len(), 1);
+
+        let Ok(Command::Generate(options)) =
+            parse("generate 12 --size 9 --seed 42")
+        else {
+            panic!()
+        };
+        assert_eq!((options.days, options.size), (vec![12], Some(9)));
+        assert_eq!(options.seed, Some(42));
+
+        let Ok(Command::Crosscheck(options)) = parse("crosscheck 8 -n 3")
+        else {
+            panic!()
+        };
+        assert_eq!((options.days, options.runs), (vec![8], 3));
+
+        let Ok(Command::Bench(options)) = parse("bench 23 -n 5 -w 0") else {
+            panic!()
+        };
+        assert_eq!((options.runs, options.warmup), (5, 0));
+
+        let Ok(Command::Run(options)) = parse("-j 8") else {
+            panic!()
+        };
+        assert_eq!(options.jobs, 8);
+        let Ok(Command::Run(options)) = parse("-j 0") else {
+            panic!()
+        };
+        assert!(options.jobs >= 1);
+
+        let Ok(Command::Run(options)) = parse("10 --example=2") else {
+            panic!()
+        };
+        assert_eq!(options.input_name(), "example-2");
+        assert_eq!(options.parts_for(10), [Part::Two]);
+        let Ok(Command::Run(options)) = parse("-e3 20") else {
+            panic!()
+        };
+        assert_eq!(options.example, Some(3));
+        assert_eq!(options.parts_for(20), Part::ALL);
+        let Ok(Command::Run(options)) = parse("-e 20") else {
+            panic!()
+        };
+        assert_eq!(options.parts_for(20), [Part::One]);
+        assert_eq!(options.parts_for(2), Part::ALL);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("run --exmaple"),
+            Err(CliError::UnknownFlag("--exmaple".to_string()))
+        );
+        assert_eq!(
+            parse("run -ex"),
+            Err(CliError::UnknownFlag("-ex".to_string()))
+        );
+        assert_eq!(
+            parse("run 26"),
+            Err(CliError::InvalidDay("26".to_string()))
+        );
+        assert_eq!(
+            parse("bench --part 3"),
+            Err(CliError::InvalidPart("3".to_string()))
+        );
+        assert_eq!(
+            parse("run --input-dir"),
+            Err(CliError::MissingValue("--input-dir".to_string()))
+        );
+        assert_eq!(
+            parse("run --format xml"),
+            Err(CliError::InvalidFormat("xml".to_string()))
+        );
+        assert_eq!(
+            parse("bench --runs 0"),
+            Err(CliError::InvalidCount("0".to_string()))
+        );
+        assert_eq!(
+            parse("run --example=0"),
+            Err(CliError::InvalidCount("0".to_string()))
+        );
+        assert_eq!(
+            parse("run -P cycles"),
+            Err(CliError::InvalidParam("cycles".to_string()))
+        );
+        assert_eq!(parse("run 1-2 -"), Err(CliError::SingleDayRequired));
+        assert_eq!(parse("run --file x"), Err(CliError::SingleDayRequired));
+        assert_eq!(parse("generate"), Err(CliError::SingleDayRequired));
+    }
+}
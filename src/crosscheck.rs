@@ -0,0 +1,165 @@
+use crate::error::AocError;
+use crate::golden::Outcome;
+use crate::params::{self, Param};
+use crate::solver::{Answer, Part};
+use crate::{day06, day08, day20, day21};
+
+pub type ReferenceFn = fn(&str, &[Param]) -> Result<Answer, AocError>;
+
+// A brute force solution of a part that relies on no property of the
+// real inputs, so it is only quick on small ones.
+pub struct Reference {
+    pub day: u8,
+    pub part: Part,
+    // the size of the generated inputs it is still quick on
+    pub size: usize,
+    // the params both solutions get for an input, before --param
+    pub params: fn(&str) -> Vec<Param>,
+    pub solve: ReferenceFn,
+}
+
+fn no_params(_: &str) -> Vec<Param> {
+    vec![]
+}
+
+// the real steps end on the edge of a garden 202300 gardens out, ten
+// gardens out walks the same shape
+fn day21_params(input: &str) -> Vec<Param> {
+    let width = input.lines().next().map_or(0, str::len);
+    let steps = 10 * width + width / 2;
+    vec![("steps_two".to_string(), steps.to_string())]
+}
+
+static REFERENCES: [Reference; 4] = [
+    Reference {
+        day: 6,
+        part: Part::Two,
+        size: 3,
+        params: no_params,
+        solve: |input, _| {
+            Ok(day06::reference_part_two(input)
+                .map_err(|e| e.into_error(6))?
+                .into())
+        },
+    },
+    Reference {
+        day: 8,
+        part: Part::Two,
+        size: 20,
+        params: no_params,
+        solve: |input, _| {
+            Ok(day08::reference_part_two(input)
+                .map_err(|e| e.into_error(8))?
+                .into())
+        },
+    },
+    Reference {
+        day: 20,
+        part: Part::Two,
+        size: 4,
+        params: no_params,
+        solve: |input, _| {
+            Ok(day20::reference_part_two(input)
+                .map_err(|e| e.into_error(20))?
+                .into())
+        },
+    },
+    Reference {
+        day: 21,
+        part: Part::Two,
+        size: 21,
+        params: day21_params,
+        solve: |input, params| {
+            let params = params::build::<day21::Params>(params)
+                .map_err(|e| e.into_error(21))?;
+            Ok(day21::reference_part_two_with(input, &params)
+                .map_err(|e| e.into_error(21))?
+                .into())
+        },
+    },
+];
+
+pub fn references(day: u8) -> impl Iterator<Item = &'static Reference> {
+    REFERENCES.iter().filter(move |r| r.day == day)
+}
+
+// runs the solver and the reference on the same input, a `Fail` has
+// the answer of the reference as the expected one
+pub fn compare(
+    reference: &Reference,
+    input: &str,
+    extra: &[Param],
+) -> Outcome {
+    let mut params = (reference.params)(input);
+    params.extend_from_slice(extra);
+    let (day, part) = (reference.day, reference.part);
+    let answer = crate::run_part_with(day, part, input, &params);
+    match (answer, (reference.solve)(input, &params)) {
+        (Ok(answer), Ok(expected)) if answer == expected => {
+            Outcome::Pass(answer)
+        }
+        (Ok(answer), Ok(expected)) => Outcome::Fail { answer, expected },
+        (Err(e), _) | (_, Err(e)) => Outcome::Error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+    use crate::{read_example, read_example_n};
+
+    #[test]
+    fn examples() {
+        for (day, input) in [
+            (6, read_example(6)),
+            (8, read_example_n(8, 2)),
+            (21, read_example(21)),
+        ] {
+            let reference = references(day).next().unwrap();
+            let outcome = compare(reference, &input, &[]);
+            assert!(matches!(outcome, Outcome::Pass(_)), "day {}", day);
+        }
+        // the example of day 20 has no rx
+        let reference = references(20).next().unwrap();
+        let outcome = compare(reference, &read_example(20), &[]);
+        assert!(outcome.is_failure());
+        assert!(references(1).next().is_none());
+    }
+
+    #[test]
+    fn generated() {
+        for reference in REFERENCES.iter() {
+            let generator = gen::generator(reference.day).unwrap();
+            for seed in 0..3 {
+                let input = generator.generate(reference.size, seed).unwrap();
+                let outcome = compare(reference, &input, &[]);
+                assert!(
+                    matches!(outcome, Outcome::Pass(_)),
+                    "day {} seed {}: {}",
+                    reference.day,
+                    seed,
+                    outcome
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mismatch() {
+        let reference = Reference {
+            solve: |_, _| Ok(0u32.into()),
+            ..*references(6).next().unwrap()
+        };
+        let outcome = compare(&reference, &read_example(6), &[]);
+        assert_eq!(
+            outcome,
+            Outcome::Fail {
+                answer: 71503usize.into(),
+                expected: 0u32.into()
+            }
+        );
+        let extra = [("x".to_string(), "1".to_string())];
+        assert!(compare(&reference, &read_example(6), &extra).is_failure());
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A sequence x0, step(x0), ... that repeats: after `prefix` steps it
+// comes back every `length` steps. `state` is the state after the number
+// of steps asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T> {
+    pub prefix: usize,
+    pub length: usize,
+    pub state: T,
+}
+
+// the step before `prefix + length` that has the same state as step n
+pub fn reduce(prefix: usize, length: usize, n: usize) -> usize {
+    match n < prefix {
+        true => n,
+        false => prefix + (n - prefix) % length,
+    }
+}
+
+fn nth<T: Clone>(start: &T, n: usize, mut step: impl FnMut(&T) -> T) -> T {
+    let mut state = start.clone();
+    for _ in 0..n {
+        state = step(&state);
+    }
+    state
+}
+
+// remembers every state, the fewest steps but the most memory
+pub fn hashed<T: Clone + Eq + Hash>(
+    start: T,
+    n: usize,
+    mut step: impl FnMut(&T) -> T,
+) -> Cycle<T> {
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states = vec![];
+    let mut state = start;
+    while !seen.contains_key(&state) {
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    let prefix = seen[&state];
+    let length = states.len() - prefix;
+    let state = states.swap_remove(reduce(prefix, length, n));
+    Cycle {
+        prefix,
+        length,
+        state,
+    }
+}
+
+// Floyd's tortoise and hare, keeps two states only
+pub fn floyd<T: Clone + PartialEq>(
+    start: T,
+    n: usize,
+    mut step: impl FnMut(&T) -> T,
+) -> Cycle<T> {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the hare is a multiple of the length ahead, so they meet where the
+    // cycle starts
+    let mut prefix = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let state = nth(&start, reduce(prefix, length, n), step);
+    Cycle {
+        prefix,
+        length,
+        state,
+    }
+}
+
+// Brent's powers of two, fewer steps than floyd with two states as well
+pub fn brent<T: Clone + PartialEq>(
+    start: T,
+    n: usize,
+    mut step: impl FnMut(&T) -> T,
+) -> Cycle<T> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut prefix = 0;
+    tortoise = start.clone();
+    hare = nth(&start, length, &mut step);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let state = nth(&start, reduce(prefix, length, n), step);
+    Cycle {
+        prefix,
+        length,
+        state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        // 0 1 2 3 4 2 3 4 ...
+        let step = |&x: &u32| if x == 4 { 2 } else { x + 1 };
+        let expected = Cycle {
+            prefix: 2,
+            length: 3,
+            state: 4,
+        };
+        assert_eq!(hashed(0, 1000, step), expected);
+        assert_eq!(floyd(0, 1000, step), expected);
+        assert_eq!(brent(0, 1000, step), expected);
+        assert_eq!(hashed(0, 1, step).state, 1);
+        assert_eq!(reduce(2, 3, 1000), 4);
+
+        // a pure cycle
+        let step = |&x: &u32| (x + 1) % 7;
+        assert_eq!(
+            (brent(0, 9, step).prefix, brent(0, 9, step).length),
+            (0, 7)
+        );
+    }
+
+    #[test]
+    fn agree() {
+        for seed in 0..50u64 {
+            let step = |&x: &u64| (x * x + seed) % 1009;
+            let brute = nth(&seed, 12345, step);
+            let a = hashed(seed, 12345, step);
+            assert_eq!(a.state, brute);
+            assert_eq!(floyd(seed, 12345, step), a);
+            assert_eq!(brent(seed, 12345, step), a);
+        }
+    }
+}
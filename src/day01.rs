@@ -1,26 +1,28 @@
-pub fn part_one(input: &str) -> u32 {
-    input
-        .trim()
-        .lines()
-        .map(|s| {
-            let a = s
-                .chars()
-                .find(|ch| ch.is_numeric())
-                .unwrap()
-                .to_digit(10)
-                .unwrap();
-            let b = s
-                .chars()
-                .rfind(|ch| ch.is_numeric())
-                .unwrap()
-                .to_digit(10)
-                .unwrap();
-            a * 10 + b
+use crate::error::ParseError;
+use crate::parse::{lines, Line};
+
+pub(crate) fn parse_input(input: &str) -> Result<Vec<Line<'_>>, ParseError> {
+    Ok(lines(input).collect())
+}
+
+pub fn try_part_one(input: &str) -> Result<u32, ParseError> {
+    parse_input(input)?
+        .into_iter()
+        .map(|line| {
+            let s = line.text;
+            let a = s.chars().find(|ch| ch.is_ascii_digit());
+            let b = s.chars().rfind(|ch| ch.is_ascii_digit());
+            match (a, b) {
+                (Some(a), Some(b)) => {
+                    Ok(a.to_digit(10).unwrap() * 10 + b.to_digit(10).unwrap())
+                }
+                _ => Err(line.error(s, "no digit in line")),
+            }
         })
         .sum()
 }
 
-pub fn part_two(input: &str) -> u32 {
+pub fn try_part_two(input: &str) -> Result<u32, ParseError> {
     fn numeric(s: &str, i: usize) -> Option<u32> {
         let words = [
             "one", "two", "three", "four", "five", "six", "seven", "eight",
@@ -38,37 +40,50 @@ pub fn part_two(input: &str) -> u32 {
         }
     }
 
-    input
-        .trim()
-        .lines()
-        .map(|s| {
+    parse_input(input)?
+        .into_iter()
+        .map(|line| {
+            let s = line.text;
+            if let Some(i) = s.find(|ch: char| !ch.is_ascii()) {
+                return Err(line.error(&s[i..], "non-ASCII character"));
+            }
             let n = s.len();
-            let a = (0..n).find_map(|i| numeric(s, i)).unwrap();
-            let b = (0..n).rev().find_map(|i| numeric(s, i)).unwrap();
-            a * 10 + b
+            let a = (0..n).find_map(|i| numeric(s, i));
+            let b = (0..n).rev().find_map(|i| numeric(s, i));
+            match (a, b) {
+                (Some(a), Some(b)) => Ok(a * 10 + b),
+                _ => Err(line.error(s, "no digit in line")),
+            }
         })
         .sum()
 }
 
+pub fn part_one(input: &str) -> u32 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u32 {
+    try_part_two(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
+    use crate::{read_example, read_example_n};
 
     #[test]
     fn example() {
         let input = read_example(1);
         assert_eq!(part_one(&input), 142);
 
-        let input = r"
-            two1nine
-            eightwothree
-            abcone2threexyz
-            xtwone3four
-            4nineeightseven2
-            zoneight234
-            7pqrstsixteen
-        ";
-        assert_eq!(part_two(input), 281);
+        let input = read_example_n(1, 2);
+        assert_eq!(part_two(&input), 281);
+    }
+
+    #[test]
+    fn malformed() {
+        let err = try_part_one("1abc2\npqrstu\n").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "pqrstu"));
+        assert!(try_part_two("1abc2\npqrstu\n").is_err());
     }
 }
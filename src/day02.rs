@@ -1,34 +1,77 @@
-fn parse_input(input: &str) -> Vec<Vec<[u32; 3]>> {
-    input
-        .trim()
-        .lines()
-        .map(|s| {
-            let (_, s) = s.split_once(':').unwrap();
+use crate::error::ParseError;
+use crate::params::{self, ParamError};
+use crate::parse::lines;
+
+// the cubes in the bag of part one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            red: 12,
+            green: 13,
+            blue: 14,
+        }
+    }
+}
+
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["red", "green", "blue"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "red" => self.red = params::value(key, value)?,
+            "green" => self.green = params::value(key, value)?,
+            "blue" => self.blue = params::value(key, value)?,
+            _ => return Err(ParamError::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<Vec<[u32; 3]>>, ParseError> {
+    lines(input)
+        .map(|line| {
+            let (_, s) = line.split_once(line.text, ":")?;
             s.trim()
                 .split("; ")
                 .map(|s| {
                     let mut cubes = [0, 0, 0];
-                    s.split(", ").for_each(|s| {
-                        let (a, b) = s.split_once(' ').unwrap();
+                    for s in s.split(", ") {
+                        let (a, b) = line.split_once(s, " ")?;
                         let c = match b {
                             "red" => 0,
                             "green" => 1,
                             "blue" => 2,
-                            _ => panic!(),
+                            _ => return Err(line.error(b, "unknown color")),
                         };
-                        cubes[c] = a.parse().unwrap()
-                    });
-                    cubes
+                        cubes[c] = line.parse(a)?;
+                    }
+                    Ok(cubes)
                 })
                 .collect()
         })
         .collect()
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let configuration = [12, 13, 14];
-    let games = parse_input(input);
-    games
+pub fn try_part_one(input: &str) -> Result<u32, ParseError> {
+    try_part_one_with(input, &Params::default())
+}
+
+pub fn try_part_one_with(
+    input: &str,
+    params: &Params,
+) -> Result<u32, ParseError> {
+    let configuration = [params.red, params.green, params.blue];
+    let games = parse_input(input)?;
+    let sum = games
         .into_iter()
         .enumerate()
         .filter(|(_, game)| {
@@ -44,12 +87,13 @@ pub fn part_one(input: &str) -> u32 {
             possible
         })
         .map(|(i, _)| i as u32 + 1)
-        .sum()
+        .sum();
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let games = parse_input(input);
-    games
+pub fn try_part_two(input: &str) -> Result<u32, ParseError> {
+    let games = parse_input(input)?;
+    let sum = games
         .into_iter()
         .map(|game| {
             let mut fewest = [0, 0, 0];
@@ -62,7 +106,16 @@ pub fn part_two(input: &str) -> u32 {
             }
             fewest[0] * fewest[1] * fewest[2]
         })
-        .sum()
+        .sum();
+    Ok(sum)
+}
+
+pub fn part_one(input: &str) -> u32 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u32 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -76,4 +129,14 @@ mod tests {
         assert_eq!(part_one(&input), 8);
         assert_eq!(part_two(&input), 2286);
     }
+
+    #[test]
+    fn malformed() {
+        let err =
+            try_part_one("Game 1: 3 red\nGame 2: 4 purple").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 11));
+        assert_eq!(err.text, "Game 2: 4 purple");
+        let err = try_part_two("Game 1 3 red").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+    }
 }
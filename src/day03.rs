@@ -1,21 +1,23 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.trim().lines().map(|s| s.chars().collect()).collect()
+use crate::error::ParseError;
+use crate::grid::Grid;
+
+pub(crate) fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    input.parse()
 }
 
 // return tuple (number, x, y, width)
-fn parse_numbers(grid: &[Vec<char>]) -> Vec<(u32, usize, usize, usize)> {
-    let w = grid[0].len();
+fn parse_numbers(grid: &Grid<char>) -> Vec<(u32, usize, usize, usize)> {
+    let w = grid.width();
 
     let mut numbers: Vec<(u32, usize, usize, usize)> = vec![];
-    // for y in 0..h {
-    for (y, row) in grid.iter().enumerate() {
+    for (y, row) in grid.rows().enumerate() {
         let mut n = 0;
         let mut s = 0;
-        // for x in 0..w {
         for (x, ch) in row.iter().enumerate() {
-            if ch.is_numeric() {
+            if ch.is_ascii_digit() {
                 n = n * 10 + ch.to_digit(10).unwrap();
                 s += 1;
             } else if s != 0 {
@@ -31,136 +33,63 @@ fn parse_numbers(grid: &[Vec<char>]) -> Vec<(u32, usize, usize, usize)> {
     numbers
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let grid = parse_input(input);
-    let h = grid.len();
-    let w = grid[0].len();
-    let numbers = parse_numbers(&grid);
-
-    let mut sum = 0;
-    for &(n, x, y, s) in numbers.iter() {
-        let is_symbol = |x: usize, y: usize| -> bool {
-            grid[y][x] != '.' && !grid[y][x].is_numeric()
-        };
-        let x1 = x;
-        let x2 = x + s;
+// the positions around a number
+fn adjacent(
+    grid: &Grid<char>,
+    x: usize,
+    y: usize,
+    s: usize,
+) -> HashSet<(usize, usize)> {
+    (x..x + s)
+        .flat_map(|x| grid.neighbours8((x, y)))
+        .filter(|&(i, j)| j != y || i < x || i >= x + s)
+        .collect()
+}
 
-        let is_adjacent_to_symbol = || {
-            // north
-            if y > 0 {
-                for i in x1..x2 {
-                    if is_symbol(i, y - 1) {
-                        return true;
-                    }
-                }
-            }
-            // south
-            if y < h - 1 {
-                for i in x1..x2 {
-                    if is_symbol(i, y + 1) {
-                        return true;
-                    }
-                }
-            }
-            // west
-            if x1 > 0 && is_symbol(x1 - 1, y) {
-                return true;
-            }
-            // east
-            if x2 < w && is_symbol(x2, y) {
-                return true;
-            }
-            // NW
-            if y > 0 && x1 > 0 && is_symbol(x1 - 1, y - 1) {
-                return true;
-            }
-            // NE
-            if y > 0 && x2 < w && is_symbol(x2, y - 1) {
-                return true;
-            }
-            // SW
-            if y < h - 1 && x1 > 0 && is_symbol(x1 - 1, y + 1) {
-                return true;
-            }
-            // SE
-            if y < h - 1 && x2 < w && is_symbol(x2, y + 1) {
-                return true;
-            }
-            false
-        };
+pub fn try_part_one(input: &str) -> Result<u32, ParseError> {
+    let grid = parse_input(input)?;
+    let numbers = parse_numbers(&grid);
 
-        if is_adjacent_to_symbol() {
-            sum += n
-        }
-    }
-    sum
+    let is_symbol = |&pos: &(usize, usize)| -> bool {
+        grid[pos] != '.' && !grid[pos].is_ascii_digit()
+    };
+    let sum = numbers
+        .iter()
+        .filter(|&&(_, x, y, s)| {
+            adjacent(&grid, x, y, s).iter().any(is_symbol)
+        })
+        .map(|&(n, _, _, _)| n)
+        .sum();
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let grid = parse_input(input);
-    let h = grid.len();
-    let w = grid[0].len();
+pub fn try_part_two(input: &str) -> Result<u32, ParseError> {
+    let grid = parse_input(input)?;
     let numbers = parse_numbers(&grid);
 
     let mut stars: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
     for &(n, x, y, s) in numbers.iter() {
-        let is_star = |x: usize, y: usize| -> bool { grid[y][x] == '*' };
-        let mut insert_star = |x: usize, y: usize| {
-            if let Some(v) = stars.get_mut(&(x, y)) {
-                v.push(n);
-            } else {
-                stars.insert((x, y), vec![n]);
-            }
-        };
-        let x1 = x;
-        let x2 = x + s;
-        // north
-        if y > 0 {
-            for i in x1..x2 {
-                if is_star(i, y - 1) {
-                    insert_star(i, y - 1);
-                }
-            }
-        }
-        // south
-        if y < h - 1 {
-            for i in x1..x2 {
-                if is_star(i, y + 1) {
-                    insert_star(i, y + 1);
-                }
+        for pos in adjacent(&grid, x, y, s) {
+            if grid[pos] == '*' {
+                stars.entry(pos).or_default().push(n);
             }
         }
-        // west
-        if x1 > 0 && is_star(x1 - 1, y) {
-            insert_star(x1 - 1, y);
-        }
-        // east
-        if x2 < w && is_star(x2, y) {
-            insert_star(x2, y);
-        }
-        // NW
-        if y > 0 && x1 > 0 && is_star(x1 - 1, y - 1) {
-            insert_star(x1 - 1, y - 1);
-        }
-        // NE
-        if y > 0 && x2 < w && is_star(x2, y - 1) {
-            insert_star(x2, y - 1);
-        }
-        // SW
-        if y < h - 1 && x1 > 0 && is_star(x1 - 1, y + 1) {
-            insert_star(x1 - 1, y + 1);
-        }
-        // SE
-        if y < h - 1 && x2 < w && is_star(x2, y + 1) {
-            insert_star(x2, y + 1);
-        }
     }
 
-    stars
+    let sum = stars
         .iter()
         .filter(|(_, v)| v.len() == 2)
         .map(|(_, v)| v[0] * v[1])
-        .sum()
+        .sum();
+    Ok(sum)
+}
+
+pub fn part_one(input: &str) -> u32 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u32 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -173,5 +102,7 @@ mod tests {
         let input = read_example(3);
         assert_eq!(part_one(&input), 4361);
         assert_eq!(part_two(&input), 467835);
+        // only ascii digits make numbers
+        assert_eq!(part_one("½1\n½1"), 2);
     }
 }
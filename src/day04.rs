@@ -1,24 +1,26 @@
-fn parse_input(input: &str) -> Vec<Vec<Vec<u32>>> {
-    input
-        .trim()
-        .lines()
-        .map(|s| {
-            s.split_once(':')
-                .unwrap()
-                .1
-                .trim()
-                .split('|')
+use crate::error::ParseError;
+use crate::parse::lines;
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<Vec<Vec<u32>>>, ParseError> {
+    lines(input)
+        .map(|line| {
+            let (_, s) = line.split_once(line.text, ":")?;
+            let (a, b) = line.split_once(s, "|")?;
+            [a, b]
+                .into_iter()
                 .map(|s| {
-                    s.split_whitespace().map(|s| s.parse().unwrap()).collect()
+                    s.split_whitespace().map(|s| line.parse(s)).collect()
                 })
                 .collect()
         })
         .collect()
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let cards = parse_input(input);
-    cards
+pub fn try_part_one(input: &str) -> Result<u32, ParseError> {
+    let cards = parse_input(input)?;
+    let sum = cards
         .iter()
         .map(|card| {
             card[1]
@@ -27,11 +29,12 @@ pub fn part_one(input: &str) -> u32 {
                 .fold(1, |a, _| a << 1)
                 >> 1
         })
-        .sum()
+        .sum();
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let cards = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let cards = parse_input(input)?;
     let points = cards
         .iter()
         .map(|card| {
@@ -55,7 +58,15 @@ pub fn part_two(input: &str) -> usize {
         //     numbers[j] += t;
         // }
     }
-    numbers.iter().sum()
+    Ok(numbers.iter().sum())
+}
+
+pub fn part_one(input: &str) -> u32 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
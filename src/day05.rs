@@ -1,44 +1,55 @@
+use crate::error::ParseError;
+use crate::parse::lines;
+
 #[allow(clippy::type_complexity)]
-fn parse_input(input: &str) -> (Vec<i64>, Vec<Vec<(i64, i64, i64)>>) {
-    fn parse_seeds(line: &str) -> Vec<i64> {
-        line.split_once(':')
-            .unwrap()
-            .1
-            .split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect()
-    }
-    let mut lines = input.lines();
-    let seeds = parse_seeds(lines.next().unwrap());
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<(Vec<i64>, Vec<Vec<(i64, i64, i64)>>), ParseError> {
+    let mut lines = lines(input);
+    let Some(line) = lines.next() else {
+        return Err(ParseError::new(1, 1, "", "empty input"));
+    };
+    let (_, s) = line.split_once(line.text, ":")?;
+    let seeds = s
+        .split_whitespace()
+        .map(|s| line.parse::<i64>(s))
+        .collect::<Result<Vec<i64>, ParseError>>()?;
 
     let mut maps: Vec<Vec<(i64, i64, i64)>> = vec![];
     let mut m: Vec<(i64, i64, i64)> = vec![];
     for line in lines {
-        if line.trim().is_empty() {
+        if line.text.trim().is_empty() {
             continue;
         }
-        if line.ends_with("map:") {
+        if line.text.ends_with("map:") {
             if !m.is_empty() {
                 maps.push(m);
                 m = vec![];
             }
             continue;
         }
-        let v: Vec<i64> = line
+        let v = line
+            .text
             .split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect();
+            .map(|s| line.parse::<i64>(s))
+            .collect::<Result<Vec<i64>, ParseError>>()?;
+        if v.len() != 3 {
+            return Err(line.error(line.text, "expected three numbers"));
+        }
+        if v[2] <= 0 {
+            return Err(line.error(line.text, "empty range"));
+        }
         m.push((v[0], v[1], v[2]));
     }
     if !m.is_empty() {
         maps.push(m);
     }
-    (seeds, maps)
+    Ok((seeds, maps))
 }
 
-pub fn part_one(input: &str) -> i64 {
-    let (seeds, maps) = parse_input(input);
-    seeds
+pub fn try_part_one(input: &str) -> Result<i64, ParseError> {
+    let (seeds, maps) = parse_input(input)?;
+    let lowest = seeds
         .iter()
         .map(|&seed| {
             maps.iter().fold(seed, |acc, m| {
@@ -55,7 +66,8 @@ pub fn part_one(input: &str) -> i64 {
             })
         })
         .min()
-        .unwrap()
+        .ok_or_else(|| ParseError::new(1, 1, "", "no seeds"))?;
+    Ok(lowest)
 }
 
 fn overlap(x: &(i64, i64), y: &(i64, i64)) -> Option<(i64, i64)> {
@@ -113,14 +125,21 @@ fn transform(
     rv
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let (seeds, maps) = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
+    let (seeds, maps) = parse_input(input)?;
+    if seeds.len() % 2 != 0
+        || seeds.iter().skip(1).step_by(2).any(|&n| n <= 0)
+    {
+        let line = lines(input).next().unwrap();
+        return Err(line.error(line.text, "invalid seed ranges"));
+    }
 
     // (begin, length) => (begin, end)
     let seeds: Vec<(i64, i64)> =
         seeds.chunks(2).map(|v| (v[0], v[0] + v[1])).collect();
 
-    maps.iter()
+    let lowest = maps
+        .iter()
         .map(|m| {
             // (destination, source, length) => (source begin, source end, delta);
             m.iter().map(|v| ((v.1, v.1 + v.2), v.0 - v.1)).collect()
@@ -128,8 +147,9 @@ pub fn part_two(input: &str) -> i64 {
         .fold(seeds, |sources, rules| transform(&sources, &rules))
         .into_iter()
         .min()
-        .unwrap()
-        .0
+        .ok_or_else(|| ParseError::new(1, 1, "", "no seeds"))?
+        .0;
+    Ok(lowest)
 
     // let mut r = seeds;
     // for m in maps {
@@ -140,9 +160,18 @@ pub fn part_two(input: &str) -> i64 {
     // r.iter().min().unwrap().0
 }
 
+pub fn part_one(input: &str) -> i64 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> i64 {
+    try_part_two(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, vec};
     use crate::read_example;
 
     #[test]
@@ -152,6 +181,29 @@ mod tests {
         assert_eq!(part_two(&input), 46);
     }
 
+    #[test]
+    fn no_seeds() {
+        let input = "seeds:\n\nseed-to-soil map:\n50 98 2\n";
+        assert_eq!(try_part_one(input).unwrap_err().reason, "no seeds");
+        assert_eq!(try_part_two(input).unwrap_err().reason, "no seeds");
+    }
+
+    #[test]
+    fn transform_keeps_length() {
+        let sources = vec((0..=100i64, 1..=20i64), 0..=5);
+        let rules = vec((0..=100i64, 1..=20i64, -50..=50i64), 0..=5);
+        check((sources, rules), |(sources, rules)| {
+            let sources: Vec<(i64, i64)> =
+                sources.iter().map(|&(a, n)| (a, a + n)).collect();
+            let rules: Vec<((i64, i64), i64)> =
+                rules.iter().map(|&(a, n, d)| ((a, a + n), d)).collect();
+            let length = |ranges: &[(i64, i64)]| {
+                ranges.iter().map(|(a, b)| b - a).sum::<i64>()
+            };
+            length(&transform(&sources, &rules)) == length(&sources)
+        });
+    }
+
     #[test]
     fn overlap_test() {
         assert_eq!(overlap(&(10, 20), &(10, 20)), Some((10, 20)));
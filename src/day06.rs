@@ -1,45 +1,81 @@
-pub fn part_one(input: &str) -> usize {
-    let numbers: Vec<Vec<u32>> = input
-        .trim()
-        .lines()
-        .map(|s| {
-            s.split_whitespace()
-                .skip(1)
-                .map(|v| v.parse().unwrap())
-                .collect()
+use crate::error::ParseError;
+use crate::parse::{lines, Line};
+
+fn parse_lines(input: &str) -> Result<[Line<'_>; 2], ParseError> {
+    let rows: Vec<Line> = lines(input).collect();
+    match rows[..] {
+        [time, distance] => Ok([time, distance]),
+        _ => {
+            let text = rows.get(2).map_or("", |line| line.text);
+            let number = rows.get(2).map_or(rows.len() + 1, |l| l.number);
+            Err(ParseError::new(number, 1, text, "expected two lines"))
+        }
+    }
+}
+
+// (time, distance) of each race
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<(u64, u64)>, ParseError> {
+    let [time, distance] = parse_lines(input)?;
+    let numbers = [time, distance]
+        .iter()
+        .map(|line| {
+            let (_, s) = line.split_once(line.text, ":")?;
+            s.split_whitespace().map(|v| line.parse(v)).collect()
         })
-        .collect();
-    let n = numbers[0].len();
-    (0..n)
-        .map(|i| (numbers[0][i], numbers[1][i]))
+        .collect::<Result<Vec<Vec<u64>>, ParseError>>()?;
+    if numbers[0].len() != numbers[1].len() {
+        return Err(distance.error(distance.text, "number of races differs"));
+    }
+    Ok(numbers[0]
+        .iter()
+        .copied()
+        .zip(numbers[1].iter().copied())
+        .collect())
+}
+
+// the single race when the spaces between the digits are ignored
+fn parse_input_kerning(input: &str) -> Result<(u64, u64), ParseError> {
+    let [time, distance] = parse_lines(input)?;
+    let numbers = [time, distance]
+        .iter()
+        .map(|line| {
+            let (_, s) = line.split_once(line.text, ":")?;
+            line.parse(&s.replace(' ', ""))
+        })
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+    Ok((numbers[0], numbers[1]))
+}
+
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let races = parse_input(input)?;
+    let product = races
+        .into_iter()
         .map(|(time, distance)| {
             (1..time).filter(|&t| (time - t) * t > distance).count()
         })
-        .product()
+        .product();
+    Ok(product)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let numbers: Vec<u64> = input
-        .trim()
-        .lines()
-        .map(|line| line.split_once(':').unwrap().1.replace(' ', ""))
-        .map(|v| v.parse().unwrap())
-        .collect();
-
-    let time = numbers[0];
-    let distance = numbers[1];
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let (time, distance) = parse_input_kerning(input)?;
 
-    let mut delta = time;
-    let mut t = time / 2;
-    while delta > 0 {
+    // the first hold that wins, the wins are symmetric around time / 2
+    let (mut lo, mut hi) = (0, time / 2);
+    if (time - hi) * hi <= distance {
+        return Ok(0);
+    }
+    while lo < hi {
+        let t = (lo + hi) / 2;
         if (time - t) * t > distance {
-            delta /= 2;
-            t -= delta;
+            hi = t;
         } else {
-            t += delta;
+            lo = t + 1;
         }
     }
-    (time + 1 - t - t) as usize
+    Ok((time + 1 - lo - lo) as usize)
 
     /*
     let mut a = 0;
@@ -61,6 +97,20 @@ pub fn part_two(input: &str) -> usize {
     // (1..time).filter(|&t| (time - t) * t > distance).count()
 }
 
+// every way to hold the button, without the binary search
+pub fn reference_part_two(input: &str) -> Result<usize, ParseError> {
+    let (time, distance) = parse_input_kerning(input)?;
+    Ok((1..time).filter(|&t| (time - t) * t > distance).count())
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,21 @@
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<(&str, i64)> {
-    input
-        .trim()
-        .lines()
-        .map(|s| s.split_once(' ').unwrap())
-        .map(|v| (v.0, v.1.parse().unwrap()))
+use crate::error::ParseError;
+use crate::parse::lines;
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<(&str, i64)>, ParseError> {
+    lines(input)
+        .map(|line| {
+            let (hand, bid) = line.split_once(line.text, " ")?;
+            if hand.len() != 5
+                || !hand.bytes().all(|b| b"AKQJT98765432".contains(&b))
+            {
+                return Err(line.error(hand, "invalid hand"));
+            }
+            Ok((hand, line.parse(bid)?))
+        })
         .collect()
 }
 
@@ -38,8 +48,8 @@ fn mask2score(mask: &[u8]) -> i32 {
     }
 }
 
-pub fn part_one(input: &str) -> i64 {
-    let mut values: Vec<(i32, Vec<u8>, &str, i64)> = parse_input(input)
+pub fn try_part_one(input: &str) -> Result<i64, ParseError> {
+    let mut values: Vec<(i32, Vec<u8>, &str, i64)> = parse_input(input)?
         .iter()
         .map(|&(hand, bid)| {
             let bytes: Vec<u8> = hand
@@ -62,15 +72,16 @@ pub fn part_one(input: &str) -> i64 {
         .collect();
     values.sort_unstable();
 
-    values
+    let winnings = values
         .iter()
         .enumerate()
         .map(|(i, v)| (i as i64 + 1) * v.3)
-        .sum()
+        .sum();
+    Ok(winnings)
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let mut values: Vec<(i32, Vec<u8>, &str, i64)> = parse_input(input)
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
+    let mut values: Vec<(i32, Vec<u8>, &str, i64)> = parse_input(input)?
         .iter()
         .map(|&(hand, bid)| {
             let bytes: Vec<u8> = hand
@@ -86,7 +97,7 @@ pub fn part_two(input: &str) -> i64 {
                 })
                 .collect();
 
-            if !bytes.iter().any(|&b| b == 0) {
+            if !bytes.contains(&0) {
                 let mask = bytes2mask(&bytes);
                 let score = mask2score(&mask);
                 (score, bytes, hand, bid)
@@ -113,11 +124,20 @@ pub fn part_two(input: &str) -> i64 {
         .collect();
     values.sort_unstable();
 
-    values
+    let winnings = values
         .iter()
         .enumerate()
         .map(|(i, v)| (i as i64 + 1) * v.3)
-        .sum()
+        .sum();
+    Ok(winnings)
+}
+
+pub fn part_one(input: &str) -> i64 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> i64 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
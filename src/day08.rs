@@ -1,24 +1,61 @@
 use std::collections::HashMap;
 
-fn parse_input(input: &str) -> (&str, HashMap<&str, (&str, &str)>) {
-    let mut lines = input.lines();
-    let instructions = lines.next().unwrap();
-    let network: HashMap<&str, (&str, &str)> = lines
-        .skip(1)
-        .map(|s| (&s[0..3], (&s[7..10], &s[12..15])))
-        .collect();
-    (instructions, network)
+use crate::error::ParseError;
+use crate::math;
+use crate::parse::{lines, Line};
+
+type Network<'a> = HashMap<&'a str, (&'a str, &'a str)>;
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<(&str, Network<'_>), ParseError> {
+    let mut lines = lines(input);
+    let Some(line) = lines.next() else {
+        return Err(ParseError::new(1, 1, "", "empty input"));
+    };
+    let instructions = line.text;
+    if let Some(i) = instructions.find(|c| c != 'L' && c != 'R') {
+        return Err(line.error(&instructions[i..], "invalid instruction"));
+    }
+    let nodes: Vec<Line> =
+        lines.filter(|line| !line.text.trim().is_empty()).collect();
+    let network = nodes
+        .iter()
+        .map(|line| {
+            let node =
+                (line.get(0..3)?, (line.get(7..10)?, line.get(12..15)?));
+            Ok(node)
+        })
+        .collect::<Result<Network, ParseError>>()?;
+    for line in nodes.iter() {
+        for next in [line.get(7..10)?, line.get(12..15)?] {
+            if !network.contains_key(next) {
+                return Err(line.error(next, "unknown node"));
+            }
+        }
+    }
+    Ok((instructions, network))
 }
 
-pub fn part_one(input: &str) -> usize {
-    let (instructions, network) = parse_input(input);
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let (instructions, network) = parse_input(input)?;
     let instructions = instructions.as_bytes();
     let n = instructions.len();
+    for node in ["AAA", "ZZZ"] {
+        if !network.contains_key(node) {
+            return Err(ParseError::new(1, 1, "", &format!("no {}", node)));
+        }
+    }
 
     let mut step = 0;
 
     let mut node = "AAA";
     while node != "ZZZ" {
+        // every (node, instruction) was seen by now, so it loops
+        if step > n * network.len() {
+            let reason = "ZZZ is never reached";
+            return Err(ParseError::new(1, 1, "", reason));
+        }
         let next = network.get(node).unwrap();
         node = match instructions[step % n] {
             b'L' => next.0,
@@ -28,78 +65,218 @@ pub fn part_one(input: &str) -> usize {
         step += 1;
     }
 
-    step
+    Ok(step)
+}
+
+// where a ghost is on a Z node: at the steps in `before` and then, from
+// `start` on, at the steps in `hits` plus any multiple of `period`
+struct Ghost {
+    before: Vec<u64>,
+    start: u64,
+    period: u64,
+    hits: Vec<u64>,
 }
 
-fn gcd(values: &[usize]) -> usize {
-    fn gcd_of_two(mut a: usize, mut b: usize) -> usize {
-        while b != 0 {
-            let remainder = a % b;
-            a = b;
-            b = remainder;
+impl Ghost {
+    fn on_z(&self, step: u64) -> bool {
+        if step < self.start {
+            return self.before.contains(&step);
         }
-        a
+        let step = self.start + (step - self.start) % self.period;
+        self.hits.contains(&step)
     }
-
-    values[1..].iter().fold(values[0], |a, &b| gcd_of_two(a, b))
 }
 
-fn lcm(values: &[usize]) -> usize {
-    let gcd = gcd(values);
-    values.iter().map(|v| v / gcd).product::<usize>() * gcd
+// walks until a (node, instruction) repeats, from there on it loops
+fn walk(instructions: &[u8], network: &Network, from: &str) -> Ghost {
+    let n = instructions.len();
+    let mut seen = HashMap::new();
+    let mut node = from;
+    let mut step = 0;
+    let mut zs = vec![];
+    let start = loop {
+        if let Some(&start) = seen.get(&(node, step % n)) {
+            break start;
+        }
+        seen.insert((node, step % n), step);
+        if node.ends_with('Z') {
+            zs.push(step as u64);
+        }
+        let next = network.get(node).unwrap();
+        node = match instructions[step % n] {
+            b'L' => next.0,
+            b'R' => next.1,
+            _ => panic!(),
+        };
+        step += 1;
+    } as u64;
+    let hits = zs.split_off(zs.partition_point(|&z| z < start));
+    Ghost {
+        before: zs,
+        start,
+        period: step as u64 - start,
+        hits,
+    }
 }
 
-pub fn part_two(input: &str) -> usize {
-    let (instructions, network) = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<u64, ParseError> {
+    let (instructions, network) = parse_input(input)?;
     let instructions = instructions.as_bytes();
-    let n = instructions.len();
 
-    // i DO think the input is the special case. so the solution is as simple
-    // as least common multiple
-    let steps: Vec<usize> = network
+    // a ghost can pass several Z nodes in its loop and need not start it
+    // at step 0, so the Z steps of the loops are lined up with the CRT
+    let ghosts: Vec<Ghost> = network
         .keys()
         .filter(|x| x.ends_with('A'))
-        .map(|&x| {
-            let mut node = x;
-            let mut step = 0;
-            loop {
-                let next = network.get(node).unwrap();
-                node = match instructions[step % n] {
-                    b'L' => next.0,
-                    b'R' => next.1,
-                    _ => panic!(),
-                };
-                step += 1;
-                if node.ends_with('Z') {
-                    break step;
+        .map(|x| walk(instructions, &network, x))
+        .collect();
+    if ghosts
+        .iter()
+        .any(|g| g.before.is_empty() && g.hits.is_empty())
+    {
+        let reason = "a ghost never reaches a Z node";
+        return Err(ParseError::new(1, 1, "", reason));
+    }
+
+    // the ghosts meet before some ghost loops, or all of them loop
+    let mut first = ghosts
+        .iter()
+        .flat_map(|g| g.before.iter().copied())
+        .filter(|&step| ghosts.iter().all(|g| g.on_z(step)))
+        .min();
+    // the steps all the loops are on Z at, modulo the lcm of the periods.
+    // the ghosts are added one at a time and a step that one of them is
+    // not on Z at is dropped right away, so this stays small.
+    let (mut steps, mut modulus) = (vec![0], 1);
+    for ghost in ghosts.iter() {
+        let mut next = vec![];
+        let mut lcm = modulus;
+        for &step in steps.iter() {
+            for &hit in ghost.hits.iter() {
+                let congruences = [(step, modulus), (hit, ghost.period)];
+                if let Some((x, m)) = math::crt(&congruences) {
+                    next.push(x);
+                    lcm = m;
                 }
             }
-        })
+        }
+        (steps, modulus) = (next, lcm);
+    }
+    // a loop is only on Z from where it starts
+    let start = ghosts.iter().map(|g| g.start).max().unwrap_or(0);
+    for step in steps {
+        let step = match step >= start {
+            true => Some(step),
+            false => (start - step)
+                .div_ceil(modulus)
+                .checked_mul(modulus)
+                .and_then(|x| x.checked_add(step)),
+        };
+        if let Some(step) = step {
+            first = Some(first.map_or(step, |first| first.min(step)));
+        }
+    }
+
+    first.ok_or_else(|| ParseError::new(1, 1, "", "the ghosts never meet"))
+}
+
+// walks every ghost at once until they are all on a Z node, without
+// assuming anything about their cycles
+pub fn reference_part_two(input: &str) -> Result<u64, ParseError> {
+    let (instructions, network) = parse_input(input)?;
+    let mut nodes: Vec<&str> = network
+        .keys()
+        .copied()
+        .filter(|x| x.ends_with('A'))
         .collect();
 
-    lcm(&steps[..])
+    let mut step = 0;
+    for &instruction in instructions.as_bytes().iter().cycle() {
+        if nodes.iter().all(|x| x.ends_with('Z')) {
+            break;
+        }
+        for node in nodes.iter_mut() {
+            let next = network[*node];
+            *node = match instruction {
+                b'L' => next.0,
+                _ => next.1,
+            };
+        }
+        step += 1;
+    }
+    Ok(step)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u64 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
+    use crate::{read_example, read_example_n};
 
     #[test]
     fn example() {
         let input = read_example(8);
         assert_eq!(part_one(&input), 6);
 
-        let input = "LR
+        let input = read_example_n(8, 2);
+        assert_eq!(part_two(&input), 6);
+    }
+
+    #[test]
+    fn offsets() {
+        // the first ghost is on Z every two steps from step 2, the second
+        // every three steps from step 1
+        let input = "L
 
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11Z, 11Z)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
 22B = (22C, 22C)
 22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)";
-        assert_eq!(part_two(input), 6);
+";
+        assert_eq!(part_two(input), 4);
+    }
+
+    #[test]
+    fn several_zs() {
+        // the first ghost is on Z at steps 1 and 2 of a loop of three, the
+        // second at step 3 of a loop of four from step 1
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (12Z, 12Z)
+12Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22D, 22D)
+22D = (22B, 22B)
+AAA = (ZZZ, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+        assert_eq!(part_two(input), 7);
+        assert_eq!(reference_part_two(input).unwrap(), 7);
+    }
+
+    #[test]
+    fn malformed() {
+        let err = try_part_one("L\n\nBBB = (BBB, BBB)").unwrap_err();
+        assert_eq!(err.reason, "no AAA");
+        let input = "L\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)";
+        let err = try_part_one(input).unwrap_err();
+        assert_eq!(err.reason, "ZZZ is never reached");
+        let err = try_part_two(input).unwrap_err();
+        assert_eq!(err.reason, "a ghost never reaches a Z node");
     }
 }
@@ -1,12 +1,21 @@
-fn parse_input(input: &str) -> Vec<Vec<i64>> {
-    input
-        .trim()
-        .lines()
-        .map(|s| s.split_whitespace().map(|v| v.parse().unwrap()).collect())
+use crate::error::ParseError;
+use crate::parse::lines;
+
+pub(crate) fn parse_input(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    lines(input)
+        .map(|line| {
+            if line.text.trim().is_empty() {
+                return Err(line.error(line.text, "empty sequence"));
+            }
+            line.text
+                .split_whitespace()
+                .map(|v| line.parse(v))
+                .collect()
+        })
         .collect()
 }
 
-pub fn part_one(input: &str) -> i64 {
+pub fn try_part_one(input: &str) -> Result<i64, ParseError> {
     fn predict(s: &[i64]) -> i64 {
         if s.iter().all(|&x| x == 0) {
             return 0;
@@ -15,11 +24,11 @@ pub fn part_one(input: &str) -> i64 {
         s.last().unwrap() + predict(&next)
     }
 
-    let input = parse_input(input);
-    input.iter().map(|sequence| predict(sequence)).sum()
+    let input = parse_input(input)?;
+    Ok(input.iter().map(|sequence| predict(sequence)).sum())
 }
 
-pub fn part_two(input: &str) -> i64 {
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
     fn predict(s: &[i64]) -> i64 {
         if s.iter().all(|&x| x == 0) {
             return 0;
@@ -28,8 +37,16 @@ pub fn part_two(input: &str) -> i64 {
         s.first().unwrap() - predict(&next)
     }
 
-    let input = parse_input(input);
-    input.iter().map(|sequence| predict(sequence)).sum()
+    let input = parse_input(input)?;
+    Ok(input.iter().map(|sequence| predict(sequence)).sum())
+}
+
+pub fn part_one(input: &str) -> i64 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> i64 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -1,112 +1,132 @@
-use std::collections::HashMap;
 use std::collections::HashSet;
 
-type Pos = (i32, i32);
-type Pipes = HashMap<Pos, [Pos; 2]>;
-
-// returns (piles as hashmap, starting position)
-fn parse_input(input: &str) -> (Pipes, Pos) {
-    let mut start = (0, 0);
-    let mut pipes = Pipes::new();
-    input.trim().lines().enumerate().for_each(|(y, s)| {
-        let y = y as i32;
-        s.chars().enumerate().for_each(|(x, c)| {
-            let x = x as i32;
-            match c {
-                '.' => {}
-                'S' => start = (x, y),
-                _ => {
-                    pipes.insert(
-                        (x, y),
-                        match c {
-                            '|' => [(x, y - 1), (x, y + 1)],
-                            '-' => [(x - 1, y), (x + 1, y)],
-                            'L' => [(x, y - 1), (x + 1, y)],
-                            'J' => [(x, y - 1), (x - 1, y)],
-                            '7' => [(x - 1, y), (x, y + 1)],
-                            'F' => [(x + 1, y), (x, y + 1)],
-                            _ => panic!(),
-                        },
-                    );
-                }
-            }
+use crate::error::ParseError;
+use crate::geom::{Dir, Point2};
+use crate::grid::Grid;
+use crate::parse::rectangle;
+use crate::visualize::{Color, Tile};
+
+type Pos = Point2<i32>;
+// the two directions a pipe connects, none for the ground
+type Pipes = Grid<Option<[Dir; 2]>>;
+
+fn connect(tile: char) -> Option<[Dir; 2]> {
+    match tile {
+        '|' => Some([Dir::N, Dir::S]),
+        '-' => Some([Dir::W, Dir::E]),
+        'L' => Some([Dir::N, Dir::E]),
+        'J' => Some([Dir::N, Dir::W]),
+        '7' => Some([Dir::W, Dir::S]),
+        'F' => Some([Dir::E, Dir::S]),
+        _ => None,
+    }
+}
+
+fn pipe(pipes: &Pipes, pos: Pos) -> Option<[Dir; 2]> {
+    *pipes.get(pos.x, pos.y)?
+}
+
+// the direction to leave a pipe entered by moving towards `dir`
+fn turn(pipe: [Dir; 2], dir: Dir) -> Dir {
+    if pipe[0] == dir.reverse() {
+        pipe[1]
+    } else {
+        pipe[0]
+    }
+}
+
+// returns (pipes, starting position)
+pub(crate) fn parse_input(input: &str) -> Result<(Pipes, Pos), ParseError> {
+    let rows = rectangle(input)?;
+    let tiles = Grid::from_lines(&rows, |c| match c {
+        '.' | 'S' | '|' | '-' | 'L' | 'J' | '7' | 'F' => Ok(c),
+        _ => Err("unknown tile"),
+    })?;
+    let mut starts = tiles.find_all(|&c| c == 'S');
+    let Some(start) = starts.next() else {
+        return Err(ParseError::new(1, 1, rows[0].text, "no start"));
+    };
+    if let Some((x, y)) = starts.next() {
+        return Err(rows[y].error(&rows[y].text[x..], "more than one start"));
+    }
+
+    let mut pipes = tiles.map(|&c| connect(c));
+    let pos = Point2::new(start.0 as i32, start.1 as i32);
+    let connected: Vec<Dir> = Dir::ALL
+        .into_iter()
+        .filter(|&dir| {
+            pipe(&pipes, pos.step(dir))
+                .is_some_and(|p| p.contains(&dir.reverse()))
         })
-    });
-
-    pipes.insert(
-        start,
-        [(1, 0), (0, 1), (-1, 0), (0, -1)]
-            .iter()
-            .map(|(dx, dy)| (start.0 + dx, start.1 + dy))
-            .filter(|pos| match pipes.get(pos) {
-                // Some(nexts) => nexts.iter().any(|next| next == &start),
-                Some(nexts) => nexts.contains(&start),
-                None => false,
-            })
-            .collect::<Vec<Pos>>()
-            .try_into()
-            .unwrap(),
-    );
-
-    (pipes, start)
+        .collect();
+    match connected.try_into() {
+        Ok(nexts) => pipes[start] = Some(nexts),
+        Err(_) => {
+            let line = &rows[start.1];
+            let at = &line.text[start.0..];
+            return Err(line.error(at, "start is not on a single loop"));
+        }
+    };
+
+    Ok((pipes, pos))
 }
 
-pub fn part_one(input: &str) -> i64 {
-    let (pipes, start) = parse_input(input);
+pub fn try_part_one(input: &str) -> Result<i64, ParseError> {
+    let (pipes, start) = parse_input(input)?;
 
     let mut step = 0;
-    let mut prev = (-1, -1);
+    let mut dir = pipe(&pipes, start).unwrap()[0];
     let mut curr = start;
 
-    while let Some(nexts) = pipes.get(&curr) {
+    loop {
         step += 1;
-        let next = nexts.iter().find(|&pos| pos != &prev).unwrap().to_owned();
-        if next == start {
+        curr = curr.step(dir);
+        if curr == start {
             break;
         }
-        prev = curr;
-        curr = next;
+        let Some(nexts) = pipe(&pipes, curr) else {
+            break;
+        };
+        dir = turn(nexts, dir);
     }
 
-    step / 2
+    Ok(step / 2)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let (pipes, start) = parse_input(input);
-
+fn find_main_loop(pipes: &Pipes, start: Pos) -> HashSet<Pos> {
     let mut main_loop: HashSet<Pos> = HashSet::new();
-    let mut prev = (-1, -1);
+    let mut dir = pipe(pipes, start).unwrap()[0];
     let mut curr = start;
 
-    while let Some(nexts) = pipes.get(&curr) {
+    while let Some(nexts) = pipe(pipes, curr) {
         main_loop.insert(curr);
-        let next = nexts.iter().find(|&pos| pos != &prev).unwrap().to_owned();
-        if next == start {
+        dir = turn(nexts, dir);
+        curr = curr.step(dir);
+        if curr == start {
             break;
         }
-        prev = curr;
-        curr = next;
     }
+    main_loop
+}
 
-    let h = input.trim().lines().count() as i32;
-    let w = input.trim().lines().next().unwrap().len() as i32;
+// the tiles enclosed by the main loop
+fn find_inside(pipes: &Pipes, main_loop: &HashSet<Pos>) -> Vec<Pos> {
+    let h = pipes.height() as i32;
+    let w = pipes.width() as i32;
 
     // Ray casting algorithm
-    let mut tiles = 0;
+    let mut tiles = vec![];
     for y in 0..h {
         let mut i = 0; // intersection
         let mut j = 0; // south and/or north
         for x in 0..w {
-            if main_loop.contains(&(x, y)) {
-                let nexts = pipes.get(&(x, y)).unwrap();
-                let pipe: u8 = [(1, 0), (0, 1), (-1, 0), (0, -1)]
+            let pos = Point2::new(x, y);
+            if main_loop.contains(&pos) {
+                // one bit per connected direction, E S W N from the lowest
+                let pipe: u8 = pipe(pipes, pos)
+                    .unwrap()
                     .iter()
-                    .enumerate()
-                    .map(|(i, (dx, dy))| (i, (x + dx, y + dy)))
-                    .filter(|(_, pos)| nexts.contains(pos))
-                    .map(|(i, _)| 1 << i)
-                    .reduce(|a, i| a | i)
-                    .unwrap();
+                    .fold(0, |bits, dir| bits | 1 << dir.index());
                 if pipe & 0b0100 == 0 {
                     i += 1;
                     j = pipe & 0b1010;
@@ -114,17 +134,59 @@ pub fn part_two(input: &str) -> usize {
                     i += 1;
                 }
             } else if i % 2 > 0 {
-                tiles += 1;
+                tiles.push(pos);
             }
         }
     }
     tiles
 }
 
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let (pipes, start) = parse_input(input)?;
+    let main_loop = find_main_loop(&pipes, start);
+    Ok(find_inside(&pipes, &main_loop).len())
+}
+
+// the main loop in box drawing characters and the tiles it encloses
+pub fn visualize(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let (pipes, start) = parse_input(input)?;
+    let main_loop = find_main_loop(&pipes, start);
+    let mut tiles = pipes.map(|_| Tile::new('.', Color::Dim));
+    for &pos in main_loop.iter() {
+        let ch = match pipe(&pipes, pos).unwrap() {
+            [Dir::N, Dir::S] | [Dir::S, Dir::N] => '│',
+            [Dir::E, Dir::W] | [Dir::W, Dir::E] => '─',
+            [Dir::N, Dir::E] | [Dir::E, Dir::N] => '└',
+            [Dir::N, Dir::W] | [Dir::W, Dir::N] => '┘',
+            [Dir::S, Dir::W] | [Dir::W, Dir::S] => '┐',
+            _ => '┌',
+        };
+        let color = if pos == start {
+            Color::Red
+        } else {
+            Color::Yellow
+        };
+        tiles[(pos.x as usize, pos.y as usize)] = Tile::new(ch, color);
+    }
+    for pos in find_inside(&pipes, &main_loop) {
+        tiles[(pos.x as usize, pos.y as usize)] =
+            Tile::new('I', Color::Green);
+    }
+    Ok(tiles)
+}
+
+pub fn part_one(input: &str) -> i64 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
+    use crate::{read_example, read_example_n};
 
     #[test]
     fn example() {
@@ -134,52 +196,20 @@ mod tests {
     }
 
     #[test]
-    fn example2() {
-        let input = "
-..........
-.S------7.
-.|F----7|.
-.||....||.
-.||....||.
-.|L-7F-J|.
-.|..||..|.
-.L--JL--J.
-..........
-";
-        assert_eq!(part_two(input), 4);
-    }
-
-    #[test]
-    fn example3() {
-        let input = "
-.F----7F7F7F7F-7....
-.|F--7||||||||FJ....
-.||.FJ||||||||L7....
-FJL7L7LJLJ||LJ.L-7..
-L--J.L7...LJS7F-7L7.
-....F-J..F7FJ|L7L7L7
-....L7.F7||L7|.L7L7|
-.....|FJLJ|FJ|F7|.LJ
-....FJL-7.||.||||...
-....L---J.LJ.LJLJ...
-";
-        assert_eq!(part_two(input), 8);
+    fn more_examples() {
+        assert_eq!(part_two(&read_example_n(10, 2)), 4);
+        assert_eq!(part_two(&read_example_n(10, 3)), 8);
+        assert_eq!(part_two(&read_example_n(10, 4)), 10);
     }
 
     #[test]
-    fn example4() {
-        let input = "
-FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L
-";
-        assert_eq!(part_two(input), 10);
+    fn visualization() {
+        let tiles = visualize(&read_example_n(10, 3)).unwrap();
+        let inside = tiles.find_all(|tile| tile.ch == 'I').count();
+        assert_eq!(inside, 8);
+        assert_eq!(
+            tiles.find_all(|tile| tile.color == Color::Red).count(),
+            1
+        );
     }
 }
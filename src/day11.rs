@@ -1,17 +1,51 @@
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<(usize, usize)> {
-    input
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(y, s)| {
-            s.chars().enumerate().filter_map(move |(x, c)| match c {
-                '#' => Some((x, y)),
-                _ => None,
-            })
-        })
-        .collect()
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::params::{self, ParamError};
+
+// how many rows or columns every empty one becomes in each part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub expansion_one: i64,
+    pub expansion_two: i64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            expansion_one: 2,
+            expansion_two: 1_000_000,
+        }
+    }
+}
+
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["expansion_one", "expansion_two"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "expansion_one" => {
+                self.expansion_one = params::value(key, value)?
+            }
+            "expansion_two" => {
+                self.expansion_two = params::value(key, value)?
+            }
+            _ => return Err(ParamError::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<(usize, usize)>, ParseError> {
+    let image = Grid::parse_with(input, |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("unknown tile"),
+    })?;
+    Ok(image.find_all(|&galaxy| galaxy).collect())
 }
 
 fn expaned_distance(galaxies: &[(usize, usize)], expand: i64) -> i64 {
@@ -40,7 +74,7 @@ fn expaned_distance(galaxies: &[(usize, usize)], expand: i64) -> i64 {
         .collect();
 
     let mut distance = 0;
-    for i in 0..galaxies.len() - 1 {
+    for i in 0..galaxies.len().saturating_sub(1) {
         for j in i..galaxies.len() {
             let (x1, y1) = galaxies[i];
             let (x2, y2) = galaxies[j];
@@ -52,14 +86,36 @@ fn expaned_distance(galaxies: &[(usize, usize)], expand: i64) -> i64 {
     distance
 }
 
+pub fn try_part_one(input: &str) -> Result<i64, ParseError> {
+    try_part_one_with(input, &Params::default())
+}
+
+pub fn try_part_one_with(
+    input: &str,
+    params: &Params,
+) -> Result<i64, ParseError> {
+    let galaxies = parse_input(input)?;
+    Ok(expaned_distance(&galaxies, params.expansion_one))
+}
+
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
+    try_part_two_with(input, &Params::default())
+}
+
+pub fn try_part_two_with(
+    input: &str,
+    params: &Params,
+) -> Result<i64, ParseError> {
+    let galaxies = parse_input(input)?;
+    Ok(expaned_distance(&galaxies, params.expansion_two))
+}
+
 pub fn part_one(input: &str) -> i64 {
-    let galaxies = parse_input(input);
-    expaned_distance(&galaxies, 2)
+    try_part_one(input).unwrap()
 }
 
 pub fn part_two(input: &str) -> i64 {
-    let galaxies = parse_input(input);
-    expaned_distance(&galaxies, 1_000_000)
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -70,9 +126,14 @@ mod tests {
     #[test]
     fn example() {
         let input = read_example(11);
-        let galaxies = parse_input(&input);
+        let galaxies = parse_input(&input).unwrap();
         assert_eq!(expaned_distance(&galaxies, 2), 374);
         assert_eq!(expaned_distance(&galaxies, 10), 1030);
         assert_eq!(expaned_distance(&galaxies, 100), 8410);
+        let params = Params {
+            expansion_two: 10,
+            ..Params::default()
+        };
+        assert_eq!(try_part_two_with(&input, &params), Ok(1030));
     }
 }
@@ -1,14 +1,25 @@
 use std::collections::HashMap;
 
-fn parse_input(input: &str) -> Vec<(&str, Vec<usize>)> {
-    input
-        .trim()
-        .lines()
-        .map(|row| match row.split_once(' ') {
-            Some((a, b)) => {
-                (a, b.split(',').map(|v| v.parse().unwrap()).collect())
+use crate::error::ParseError;
+use crate::parse::lines;
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<(&str, Vec<usize>)>, ParseError> {
+    lines(input)
+        .map(|line| {
+            let (a, b) = line.split_once(line.text, " ")?;
+            if let Some(i) = a.find(|c| !matches!(c, '.' | '#' | '?')) {
+                return Err(line.error(&a[i..], "unknown spring"));
+            }
+            let nums = b
+                .split(',')
+                .map(|v| line.parse(v))
+                .collect::<Result<Vec<usize>, ParseError>>()?;
+            if nums.contains(&0) {
+                return Err(line.error(b, "empty group"));
             }
-            _ => panic!(),
+            Ok((a, nums))
         })
         .collect()
 }
@@ -61,18 +72,19 @@ fn count<'a>(
     result
 }
 
-pub fn part_one(input: &str) -> usize {
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
     let mut cache: HashMap<(&[u8], &[usize]), usize> = HashMap::new();
-    parse_input(input)
+    let sum = parse_input(input)?
         .iter()
         .map(|(mask, nums)| (mask.as_bytes(), &nums[..]))
         .map(|(mask, nums)| count(mask, nums, &mut cache))
-        .sum()
+        .sum();
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> usize {
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
     let mut cache: HashMap<(&[u8], &[usize]), usize> = HashMap::new();
-    parse_input(input)
+    let sum = parse_input(input)?
         .iter()
         .map(|(mask, nums)| (mask.as_bytes(), &nums[..]))
         .map(|(mask, nums)| {
@@ -91,12 +103,22 @@ pub fn part_two(input: &str) -> usize {
         //     )
         // })
         .map(|(mask, nums)| count(mask, nums, &mut cache))
-        .sum()
+        .sum();
+    Ok(sum)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, one_of, vec};
     use crate::read_example;
 
     #[test]
@@ -105,4 +127,32 @@ mod tests {
         assert_eq!(part_one(&input), 21);
         assert_eq!(part_two(&input), 525152);
     }
+
+    // every way to fill in the '?'s
+    fn brute_force(mask: &[u8], nums: &[usize]) -> usize {
+        let unknown: Vec<usize> =
+            (0..mask.len()).filter(|&i| mask[i] == b'?').collect();
+        (0..1usize << unknown.len())
+            .filter(|bits| {
+                let mut row = mask.to_vec();
+                for (j, &i) in unknown.iter().enumerate() {
+                    row[i] = if bits >> j & 1 == 1 { b'#' } else { b'.' };
+                }
+                let groups: Vec<usize> = row
+                    .split(|&c| c == b'.')
+                    .filter(|group| !group.is_empty())
+                    .map(|group| group.len())
+                    .collect();
+                groups == nums
+            })
+            .count()
+    }
+
+    #[test]
+    fn brute_force_agrees() {
+        let strategy = (vec(one_of(b".#?"), 0..=12), vec(1..=4usize, 0..=4));
+        check(strategy, |(mask, nums)| {
+            count(mask, nums, &mut HashMap::new()) == brute_force(mask, nums)
+        });
+    }
 }
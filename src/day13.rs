@@ -1,116 +1,76 @@
-use std::convert::From;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::parse::paragraphs;
 
 #[derive(Debug)]
-struct Pattern(Vec<Vec<char>>);
+pub(crate) struct Pattern(Grid<char>);
 
 impl Pattern {
-    fn width(&self) -> usize {
-        self.0[0].len()
-    }
-
-    fn height(&self) -> usize {
-        self.0.len()
-    }
-
-    fn at(&self, x: usize, y: usize) -> char {
-        self.0[y][x]
-    }
-
-    fn row_diff(&self, y1: usize, y2: usize) -> usize {
-        (0..self.width())
-            .map(|x| self.at(x, y1) == self.at(x, y2))
-            .filter(|&v| !v)
-            .count()
-    }
-
-    fn column_diff(&self, x1: usize, x2: usize) -> usize {
-        (0..self.height())
-            .map(|y| self.at(x1, y) == self.at(x2, y))
-            .filter(|&v| !v)
-            .count()
-    }
-
-    fn is_row_reflact_at(&self, y: usize, d: usize) -> bool {
-        let mut diff = 0;
-        let mut a = y;
-        let mut b = y + 1;
-        loop {
-            diff += self.row_diff(a, b);
-            if diff > d {
-                return false;
-            }
-            if a == 0 || b >= self.height() - 1 {
-                return diff == d;
-            }
-            a -= 1;
-            b += 1;
-        }
-    }
-
-    fn is_column_reflact_at(&self, x: usize, d: usize) -> bool {
-        let mut diff = 0;
-        let mut a = x;
-        let mut b = x + 1;
-        loop {
-            diff += self.column_diff(a, b);
-            if diff > d {
-                return false;
-            }
-            if a == 0 || b >= self.width() - 1 {
-                return diff == d;
-            }
-            a -= 1;
-            b += 1;
-        }
-    }
-
     fn row_reflact_line(&self, d: usize) -> usize {
-        for y in 0..&self.height() - 1 {
-            if self.is_row_reflact_at(y, d) {
-                return y + 1;
-            }
-        }
-        0
+        reflact_line(&self.0, d)
     }
 
     fn column_reflact_line(&self, d: usize) -> usize {
-        for x in 0..&self.width() - 1 {
-            if self.is_column_reflact_at(x, d) {
-                return x + 1;
-            }
-        }
-        0
+        reflact_line(&self.0.transpose(), d)
     }
 }
 
-impl From<&str> for Pattern {
-    fn from(value: &str) -> Self {
-        Self(value.lines().map(|row| row.chars().collect()).collect())
-    }
+// the number of rows above the line that reflects the grid with exactly
+// `d` different tiles, 0 if there is no such line
+fn reflact_line(grid: &Grid<char>, d: usize) -> usize {
+    let row_diff = |y1: usize, y2: usize| {
+        let (a, b) = (grid.row(y1), grid.row(y2));
+        a.iter().zip(b).filter(|(a, b)| a != b).count()
+    };
+    (1..grid.height())
+        .find(|&y| {
+            let mut diff = 0;
+            for (a, b) in (0..y).rev().zip(y..grid.height()) {
+                diff += row_diff(a, b);
+                if diff > d {
+                    return false;
+                }
+            }
+            diff == d
+        })
+        .unwrap_or(0)
 }
 
-fn parse_input(input: &str) -> Vec<Pattern> {
-    input.split("\n\n").map(Pattern::from).collect()
+pub(crate) fn parse_input(input: &str) -> Result<Vec<Pattern>, ParseError> {
+    paragraphs(input)
+        .iter()
+        .map(|rows| Ok(Pattern(Grid::from_lines(rows, Ok)?)))
+        .collect()
 }
 
-pub fn part_one(input: &str) -> usize {
-    let patterns = parse_input(input);
-    patterns
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let patterns = parse_input(input)?;
+    let sum = patterns
         .iter()
         .map(|pattern| {
             pattern.row_reflact_line(0) * 100 + pattern.column_reflact_line(0)
         })
-        .sum()
+        .sum();
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let patterns = parse_input(input);
-    patterns
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let patterns = parse_input(input)?;
+    let sum = patterns
         .iter()
         .map(|pattern| {
             pattern.row_reflact_line(1) * 100 + pattern.column_reflact_line(1)
         })
-        .sum()
+        .sum();
+    Ok(sum)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -1,89 +1,65 @@
-use std::collections::HashMap;
-
-fn tilt_north(grid: &mut [Vec<char>]) {
-    let h = grid[0].len();
-    let w = grid.len();
-    for y in 1..h {
-        for x in 0..w {
-            if grid[y][x] == 'O' {
-                for i in (0..y).rev() {
-                    if grid[i][x] != '.' {
-                        break;
-                    }
-                    grid[i + 1][x] = '.';
-                    grid[i][x] = 'O';
-                }
-            }
-        }
-    }
+use crate::cycle;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::params::{self, ParamError};
+use crate::visualize::{Color, Tile};
+
+// the spin cycles of part two
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub cycles: usize,
 }
 
-fn tilt_south(grid: &mut [Vec<char>]) {
-    let h = grid[0].len();
-    let w = grid.len();
-    for y in (0..h).rev() {
-        for x in 0..w {
-            if grid[y][x] == 'O' {
-                for i in y + 1..h {
-                    if grid[i][x] != '.' {
-                        break;
-                    }
-                    grid[i - 1][x] = '.';
-                    grid[i][x] = 'O';
-                }
-            }
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            cycles: 1_000_000_000,
         }
     }
 }
 
-fn tilt_west(grid: &mut [Vec<char>]) {
-    let h = grid[0].len();
-    let w = grid.len();
-    for x in 1..w {
-        #[allow(clippy::needless_range_loop)]
-        for y in 0..h {
-            if grid[y][x] == 'O' {
-                for i in (0..x).rev() {
-                    if grid[y][i] != '.' {
-                        break;
-                    }
-                    grid[y][i + 1] = '.';
-                    grid[y][i] = 'O';
-                }
-            }
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["cycles"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "cycles" => self.cycles = params::value(key, value)?,
+            _ => return Err(ParamError::unknown(key)),
         }
+        Ok(())
     }
 }
 
-fn tilt_east(grid: &mut [Vec<char>]) {
-    let h = grid[0].len();
-    let w = grid.len();
-    for x in (0..w).rev() {
-        #[allow(clippy::needless_range_loop)]
-        for y in 0..h {
-            if grid[y][x] == 'O' {
-                for i in x + 1..w {
-                    if grid[y][i] != '.' {
-                        break;
-                    }
-                    grid[y][i - 1] = '.';
-                    grid[y][i] = 'O';
+fn tilt_north(grid: &mut Grid<char>) {
+    for x in 0..grid.width() {
+        // where the next rounded rock stops
+        let mut free = 0;
+        for y in 0..grid.height() {
+            match grid[(x, y)] {
+                'O' => {
+                    grid[(x, y)] = '.';
+                    grid[(x, free)] = 'O';
+                    free += 1;
                 }
+                '#' => free = y + 1,
+                _ => {}
             }
         }
     }
 }
 
-fn spin(grid: &mut [Vec<char>]) {
-    tilt_north(grid);
-    tilt_west(grid);
-    tilt_south(grid);
-    tilt_east(grid);
+// tilts north, west, south and east; each quarter turn clockwise brings
+// the next side to the north
+fn spin(grid: &mut Grid<char>) {
+    for _ in 0..4 {
+        tilt_north(grid);
+        *grid = grid.rotate_cw();
+    }
 }
 
-fn total_load(grid: &[Vec<char>]) -> usize {
-    let h = grid.len();
-    grid.iter()
+fn total_load(grid: &Grid<char>) -> usize {
+    let h = grid.height();
+    grid.rows()
         .map(|row| row.iter().filter(|&&ch| ch == 'O').count())
         .enumerate()
         .map(|(i, v)| ((h - i), v))
@@ -91,43 +67,53 @@ fn total_load(grid: &[Vec<char>]) -> usize {
         .sum()
 }
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|row| row.chars().collect()).collect()
+pub(crate) fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse_with(input, |c| match c {
+        'O' | '#' | '.' => Ok(c),
+        _ => Err("unknown tile"),
+    })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut grid = parse_input(input);
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let mut grid = parse_input(input)?;
     tilt_north(&mut grid);
-    total_load(&grid)
+    Ok(total_load(&grid))
 }
 
-pub fn part_two(input: &str) -> usize {
-    const CYCLES: usize = 1000000000;
-
-    let mut cache: HashMap<Vec<Vec<char>>, usize> = HashMap::new();
-    let mut grid = parse_input(input);
-
-    let mut i = 0;
-    let mut looped: Option<usize> = None;
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    try_part_two_with(input, &Params::default())
+}
 
-    while i < CYCLES {
-        i += 1;
+pub fn try_part_two_with(
+    input: &str,
+    params: &Params,
+) -> Result<usize, ParseError> {
+    let grid = parse_input(input)?;
+    let spun = cycle::hashed(grid, params.cycles, |grid| {
+        let mut grid = grid.clone();
         spin(&mut grid);
-        if let Some(v) = cache.get(&grid) {
-            looped = Some(*v);
-            break;
-        }
-        cache.insert(grid.clone(), i);
-    }
+        grid
+    });
+    Ok(total_load(&spun.state))
+}
 
-    if let Some(j) = looped {
-        let w = (CYCLES - i) % (i - j);
-        for _ in 0..w {
-            spin(&mut grid);
-        }
-    }
+// the rocks after tilting north
+pub fn visualize(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let mut grid = parse_input(input)?;
+    tilt_north(&mut grid);
+    Ok(grid.map(|&c| match c {
+        'O' => Tile::new('O', Color::Yellow),
+        '#' => Tile::new('#', Color::Plain),
+        _ => Tile::new('.', Color::Dim),
+    }))
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
 
-    total_load(&grid)
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -140,5 +126,9 @@ mod tests {
         let input = read_example(14);
         assert_eq!(part_one(&input), 136);
         assert_eq!(part_two(&input), 64);
+
+        let tiles = visualize(&input).unwrap();
+        let row: String = tiles.row(0).iter().map(|t| t.ch).collect();
+        assert_eq!(row, "OOOO.#.O..");
     }
 }
@@ -1,5 +1,40 @@
-fn parse_input(input: &str) -> Vec<&str> {
-    input.trim().split(',').collect()
+use crate::error::ParseError;
+use crate::parse::{lines, Line};
+
+fn parse_line(input: &str) -> Result<Line<'_>, ParseError> {
+    let mut lines = lines(input);
+    match (lines.next(), lines.next()) {
+        (Some(line), None) => Ok(line),
+        (_, Some(line)) => {
+            Err(line.error(line.text, "expected a single line"))
+        }
+        (None, None) => Err(ParseError::new(1, 1, "", "empty input")),
+    }
+}
+
+pub(crate) fn parse_input(input: &str) -> Result<Vec<&str>, ParseError> {
+    let line = parse_line(input)?;
+    Ok(line.text.trim().split(',').collect())
+}
+
+// (label, focal length) where no focal length means removing the lens
+#[allow(clippy::type_complexity)]
+fn parse_steps(
+    input: &str,
+) -> Result<Vec<(&str, Option<usize>)>, ParseError> {
+    let line = parse_line(input)?;
+    line.text
+        .trim()
+        .split(',')
+        .map(|step| {
+            if let Some(label) = step.strip_suffix('-') {
+                Ok((label, None))
+            } else {
+                let (label, focal_length) = line.split_once(step, "=")?;
+                Ok((label, Some(line.parse(focal_length)?)))
+            }
+        })
+        .collect()
 }
 
 fn hash(s: &str) -> usize {
@@ -12,19 +47,17 @@ fn hash(s: &str) -> usize {
     value
 }
 
-pub fn part_one(input: &str) -> usize {
-    let sequence = parse_input(input);
-    sequence.iter().map(|v| hash(v)).sum()
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let sequence = parse_input(input)?;
+    Ok(sequence.iter().map(|v| hash(v)).sum())
 }
 
-pub fn part_two(input: &str) -> usize {
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
     let mut boxes: Vec<Vec<(&str, usize)>> = vec![vec![]; 256];
-    let sequence = parse_input(input);
-    sequence.iter().for_each(|code| {
-        let lens: Vec<&str> = code.split(['=', '-']).collect();
-        let label = lens[0];
+    let sequence = parse_steps(input)?;
+    sequence.iter().for_each(|&(label, focal_length)| {
         let slots = &mut boxes[hash(label)];
-        if let Ok(focal_length) = lens[1].parse::<usize>() {
+        if let Some(focal_length) = focal_length {
             if let Some(lens) = slots.iter_mut().find(|v| v.0 == label) {
                 lens.1 = focal_length;
             } else {
@@ -34,7 +67,7 @@ pub fn part_two(input: &str) -> usize {
             slots.retain(|v| v.0 != label);
         }
     });
-    boxes
+    let power = boxes
         .iter()
         .enumerate()
         .filter(|(_, v)| !v.is_empty())
@@ -47,12 +80,22 @@ pub fn part_two(input: &str) -> usize {
                 })
                 .sum::<usize>()
         })
-        .sum()
+        .sum();
+    Ok(power)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, vec};
     use crate::read_example;
 
     #[test]
@@ -60,6 +103,13 @@ mod tests {
         assert_eq!(hash("HASH"), 52);
     }
 
+    #[test]
+    fn hash_in_range() {
+        check(vec(b' '..=b'~', 0..=20), |bytes| {
+            hash(std::str::from_utf8(bytes).unwrap()) < 256
+        });
+    }
+
     #[test]
     fn example() {
         let input = read_example(15);
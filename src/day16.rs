@@ -1,77 +1,35 @@
 use std::collections::HashSet;
 
-// 0:E 1:S 2:W 3:N
-// const DIRECTION: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+use crate::error::ParseError;
+use crate::geom::{Dir, Point2};
+use crate::grid::Grid;
+use crate::visualize::{Color, Tile};
 
-type Beam = (i32, i32, char);
+type Pos = Point2<i32>;
+type Beam = (Pos, Dir);
 
 struct Contraption {
-    grid: Vec<Vec<char>>,
+    grid: Grid<char>,
     beams: Vec<Beam>,
-    energized: HashSet<(i32, i32)>,
+    energized: HashSet<Pos>,
     track: HashSet<Beam>,
 }
 
 impl Contraption {
-    fn width(&self) -> i32 {
-        self.grid[0].len() as i32
-    }
-
-    fn height(&self) -> i32 {
-        self.grid.len() as i32
-    }
-
-    fn tile(&self, x: i32, y: i32) -> char {
-        self.grid[y as usize][x as usize]
-    }
-
-    fn move_beam(&self, beam: &Beam) -> Vec<Beam> {
-        let (x, y) = match *beam {
-            (x, y, 'E') => (x + 1, y),
-            (x, y, 'S') => (x, y + 1),
-            (x, y, 'W') => (x - 1, y),
-            (x, y, 'N') => (x, y - 1),
-            _ => panic!(),
-        };
-        if x < 0 || x >= self.width() || y < 0 || y >= self.height() {
+    fn move_beam(&self, &(pos, dir): &Beam) -> Vec<Beam> {
+        let pos = pos.step(dir);
+        let Some(&tile) = self.grid.get(pos.x, pos.y) else {
             return vec![];
-        }
-        let tile = self.tile(x, y);
-        let mut beams: Vec<Beam> = match (tile, beam.2) {
-            ('|', 'E' | 'W') => {
-                vec!['S', 'N']
-            }
-            ('-', 'S' | 'N') => {
-                vec!['E', 'W']
-            }
-            ('/', 'E') => {
-                vec!['N']
-            }
-            ('/', 'S') => {
-                vec!['W']
-            }
-            ('/', 'W') => {
-                vec!['S']
-            }
-            ('/', 'N') => {
-                vec!['E']
-            }
-            ('\\', 'E') => {
-                vec!['S']
-            }
-            ('\\', 'S') => {
-                vec!['E']
-            }
-            ('\\', 'W') => {
-                vec!['N']
-            }
-            ('\\', 'N') => {
-                vec!['W']
-            }
-            (_, d) => vec![d],
+        };
+        let mut beams: Vec<Beam> = match (tile, dir.is_horizontal()) {
+            ('|', true) => vec![Dir::S, Dir::N],
+            ('-', false) => vec![Dir::E, Dir::W],
+            ('/', true) | ('\\', false) => vec![dir.turn_left()],
+            ('/', false) | ('\\', true) => vec![dir.turn_right()],
+            _ => vec![dir],
         }
         .into_iter()
-        .map(|d| (x, y, d))
+        .map(|d| (pos, d))
         .collect();
 
         beams.retain(|v| !self.track.contains(v));
@@ -86,20 +44,22 @@ impl Contraption {
         }
         for beam in &new_beams {
             self.track.insert(*beam);
-            self.energized.insert((beam.0, beam.1));
+            self.energized.insert(beam.0);
         }
         self.beams = new_beams;
         self.beams.len()
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.trim().lines().map(|s| s.chars().collect()).collect()
+pub(crate) fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse_with(input, |c| match c {
+        '.' | '|' | '-' | '/' | '\\' => Ok(c),
+        _ => Err("unknown tile"),
+    })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let grid = parse_input(input);
-    let start = (-1, 0, 'E');
+// the tiles a beam entering from `start` energizes
+fn energize(grid: Grid<char>, start: Beam) -> HashSet<Pos> {
     let mut contraption = Contraption {
         grid,
         beams: vec![start],
@@ -107,35 +67,61 @@ pub fn part_one(input: &str) -> usize {
         track: HashSet::new(),
     };
     while contraption.tick() > 0 {}
-    contraption.energized.len()
+    contraption.energized
 }
-pub fn part_two(input: &str) -> usize {
-    let grid = parse_input(input);
-    let w = grid[0].len() as i32;
-    let h = grid.len() as i32;
-    let mut starts: Vec<(i32, i32, char)> = vec![];
+
+const START: Beam = (Point2::new(-1, 0), Dir::E);
+
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let grid = parse_input(input)?;
+    Ok(energize(grid, START).len())
+}
+
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let grid = parse_input(input)?;
+    let w = grid.width() as i32;
+    let h = grid.height() as i32;
+    let mut starts: Vec<Beam> = vec![];
     for i in 0..w {
-        starts.push((i, -1, 'S'));
-        starts.push((i, h, 'N'));
+        starts.push((Point2::new(i, -1), Dir::S));
+        starts.push((Point2::new(i, h), Dir::N));
     }
     for i in 0..h {
-        starts.push((-1, i, 'E'));
-        starts.push((w, i, 'W'));
+        starts.push((Point2::new(-1, i), Dir::E));
+        starts.push((Point2::new(w, i), Dir::W));
     }
-    starts
+    let energized = starts
         .into_iter()
-        .map(|start| {
-            let mut contraption = Contraption {
-                grid: grid.clone(),
-                beams: vec![start],
-                energized: HashSet::new(),
-                track: HashSet::new(),
-            };
-            while contraption.tick() > 0 {}
-            contraption.energized.len()
-        })
+        .map(|start| energize(grid.clone(), start).len())
         .max()
-        .unwrap()
+        .unwrap();
+    Ok(energized)
+}
+
+// the tiles energized in part one, with the mirrors and splitters
+pub fn visualize(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let grid = parse_input(input)?;
+    let energized = energize(grid.clone(), START);
+    let mut tiles = grid.map(|&c| match c {
+        '.' => Tile::new('.', Color::Dim),
+        _ => Tile::new(c, Color::Cyan),
+    });
+    for pos in energized {
+        let tile = &mut tiles[(pos.x as usize, pos.y as usize)];
+        *tile = match tile.ch {
+            '.' => Tile::new('#', Color::Yellow),
+            c => Tile::new(c, Color::Red),
+        };
+    }
+    Ok(tiles)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -148,5 +134,12 @@ mod tests {
         let input = read_example(16);
         assert_eq!(part_one(&input), 46);
         assert_eq!(part_two(&input), 51);
+
+        let tiles = visualize(&input).unwrap();
+        let energized = tiles
+            .iter()
+            .filter(|(_, t)| matches!(t.color, Color::Yellow | Color::Red))
+            .count();
+        assert_eq!(energized, 46);
     }
 }
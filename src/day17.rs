@@ -1,37 +1,28 @@
-use std::collections::BinaryHeap;
-use std::collections::HashSet;
+use crate::error::ParseError;
+use crate::geom::{Dir, Point2};
+use crate::grid::Grid;
+use crate::search;
 
-fn find_minimal_path(grid: &[Vec<i32>], steps_range: (i32, i32)) -> i32 {
-    const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-    let w = grid[0].len() as i32;
-    let h = grid.len() as i32;
-    let start = (0, 0);
-    let end = (w - 1, h - 1);
+type Pos = Point2<i32>;
+// (position, direction, moving steps of the direction), no direction
+// before the first move
+type State = (Pos, Option<Dir>, i32);
 
-    // (x, y, direction, moving steps of the direction)
-    let mut visited = HashSet::<(i32, i32, i32, i32)>::new();
-    // (amount of heat loss, (x, y), (direction, steps))
-    let mut queue = BinaryHeap::<(i32, (i32, i32), (i32, i32))>::new();
-
-    queue.push((0, (start), (-1, 0)));
-
-    while let Some((amount, (x, y), (direction, steps))) = queue.pop() {
-        if steps >= steps_range.0 && (x, y) == end {
-            return -amount;
-        }
-
-        for (nd, &(dx, dy)) in DIRS.iter().enumerate() {
-            let next_direction = nd as i32;
-            let next_steps = if direction < 0 {
-                // starting
-                1
-            } else if next_direction == direction {
+fn moves(
+    grid: &Grid<i32>,
+    steps_range: (i32, i32),
+    &(pos, direction, steps): &State,
+) -> Vec<(State, i32)> {
+    let mut moves = vec![];
+    for next_direction in Dir::ALL {
+        let next_steps = if let Some(direction) = direction {
+            if next_direction == direction {
                 // same direction
                 if steps >= steps_range.1 {
                     continue;
                 }
                 steps + 1
-            } else if next_direction % 2 == direction % 2 {
+            } else if next_direction == direction.reverse() {
                 // reverse direction
                 continue;
             } else {
@@ -40,59 +31,78 @@ fn find_minimal_path(grid: &[Vec<i32>], steps_range: (i32, i32)) -> i32 {
                     continue;
                 }
                 1
-            };
-            let (x, y) = (x + dx, y + dy);
-            if x < 0 || x >= w || y < 0 || y >= h {
-                // out of bound
-                continue;
             }
-            let next_amount = amount - grid[y as usize][x as usize];
-            if !visited.insert((x, y, next_direction, next_steps)) {
-                continue;
-            }
-            queue.push((next_amount, (x, y), (next_direction, next_steps)));
-        }
+        } else {
+            // starting
+            1
+        };
+        let pos = pos.step(next_direction);
+        let Some(&heat_loss) = grid.get(pos.x, pos.y) else {
+            // out of bound
+            continue;
+        };
+        moves.push(((pos, Some(next_direction), next_steps), heat_loss));
     }
-    0
+    moves
+}
+
+fn find_minimal_path(grid: &Grid<i32>, steps_range: (i32, i32)) -> i32 {
+    let w = grid.width() as i32;
+    let h = grid.height() as i32;
+    let start = Point2::new(0, 0);
+    let end = Point2::new(w - 1, h - 1);
+
+    // a block can lose no heat, so there is no distance to guide it
+    search::dijkstra(
+        (start, None, 0),
+        |state| moves(grid, steps_range, state),
+        |&(pos, _, steps)| pos == end && steps >= steps_range.0,
+    )
+    .map_or(0, |path| path.cost)
 }
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input
-        .trim()
-        .lines()
-        .map(|s| s.chars().map(|c| c.to_digit(10).unwrap() as i32).collect())
-        .collect()
+pub(crate) fn parse_input(input: &str) -> Result<Grid<i32>, ParseError> {
+    Grid::parse_with(input, |c| match c.to_digit(10) {
+        Some(v) => Ok(v as i32),
+        None => Err("not a digit"),
+    })
+}
+
+pub fn try_part_one(input: &str) -> Result<i32, ParseError> {
+    let grid = parse_input(input)?;
+    Ok(find_minimal_path(&grid, (1, 3)))
+}
+
+pub fn try_part_two(input: &str) -> Result<i32, ParseError> {
+    let grid = parse_input(input)?;
+    Ok(find_minimal_path(&grid, (4, 10)))
 }
 
 pub fn part_one(input: &str) -> i32 {
-    let grid = parse_input(input);
-    find_minimal_path(&grid, (1, 3))
+    try_part_one(input).unwrap()
 }
 
 pub fn part_two(input: &str) -> i32 {
-    let grid = parse_input(input);
-    find_minimal_path(&grid, (4, 10))
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
-
-    #[test]
-    fn examnple2() {
-        let input = "111111111111\n\
-            999999999991\n\
-            999999999991\n\
-            999999999991\n\
-            999999999991";
-        assert_eq!(part_two(input), 71);
-    }
+    use crate::{read_example, read_example_n};
 
     #[test]
     fn example() {
         let input = read_example(17);
         assert_eq!(part_one(&input), 102);
         assert_eq!(part_two(&input), 94);
+        assert_eq!(part_two(&read_example_n(17, 2)), 71);
+    }
+
+    #[test]
+    fn no_heat_loss() {
+        // east, three south and three east lose nothing
+        let input = "00000\n00010\n00110\n00000";
+        assert_eq!(part_one(input), 0);
     }
 }
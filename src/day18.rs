@@ -1,51 +1,63 @@
-fn parse_input(input: &str) -> Vec<(usize, i32)> {
-    input
-        .trim()
-        .lines()
+use crate::error::ParseError;
+use crate::geom::{Dir, Point2};
+use crate::grid::Grid;
+use crate::parse::{lines, Line};
+use crate::visualize::{Color, Tile};
+
+fn parse_fields<'a>(line: &Line<'a>) -> Result<[&'a str; 3], ParseError> {
+    let v: Vec<&str> = line.text.split_whitespace().collect();
+    v.try_into()
+        .map_err(|_| line.error(line.text, "expected three fields"))
+}
+
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<Vec<(Dir, i32)>, ParseError> {
+    lines(input)
         .map(|line| {
-            let v: Vec<&str> = line.split_whitespace().collect();
-            (
-                match v[0] {
-                    "R" => 0,
-                    "D" => 1,
-                    "L" => 2,
-                    "U" => 3,
-                    _ => panic!(),
-                },
-                v[1].parse::<i32>().unwrap(),
-            )
+            let [direction, count, _] = parse_fields(&line)?;
+            let dir = direction.parse().ok().and_then(Dir::from_char);
+            let direction = dir
+                .ok_or_else(|| line.error(direction, "unknown direction"))?;
+            Ok((direction, line.parse::<i32>(count)?))
         })
         .collect()
 }
 
-fn parse_input_hex(input: &str) -> Vec<(usize, i32)> {
-    input
-        .trim()
-        .lines()
+// The last hexadecimal digit encodes the direction to dig:
+// 0 means R, 1 means D, 2 means L, and 3 means U, the order of Dir::ALL.
+fn parse_input_hex(input: &str) -> Result<Vec<(Dir, i32)>, ParseError> {
+    lines(input)
         .map(|line| {
-            let v: Vec<&str> = line.split_whitespace().collect();
-            (
-                usize::from_str_radix(&v[2][7..8], 16).unwrap(),
-                i32::from_str_radix(&v[2][2..7], 16).unwrap(),
-            )
+            let [_, _, color] = parse_fields(&line)?;
+            let hex = color
+                .strip_prefix("(#")
+                .and_then(|s| s.strip_suffix(')'))
+                .filter(|s| s.len() == 6 && s.is_ascii())
+                .ok_or_else(|| line.error(color, "expected (#rrggbb)"))?;
+            let count = i32::from_str_radix(&hex[0..5], 16)
+                .map_err(|_| line.error(hex, "invalid hexadecimal"))?;
+            let digit = &hex[5..6];
+            let direction = digit
+                .parse::<char>()
+                .ok()
+                .and_then(|c| c.to_digit(4))
+                .map(|d| Dir::ALL[d as usize])
+                .ok_or_else(|| line.error(digit, "unknown direction"))?;
+            Ok((direction, count))
         })
         .collect()
 }
 
-// The last hexadecimal digit encodes the direction to dig:
-// 0 means R, 1 means D, 2 means L, and 3 means U.
-const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-pub fn part_one(input: &str) -> usize {
-    let plan = parse_input(input);
-
+// the trench '#', the lagoon inside it '.' and the ground outside '+'
+fn dig(plan: &[(Dir, i32)]) -> Grid<char> {
     // build grid
     let (mut x0, mut y0) = (0, 0);
     let (mut x1, mut y1) = (0, 0);
     let (mut x, mut y) = (0, 0);
     let mut trench: Vec<(i32, i32)> = vec![];
     for &(d, s) in plan.iter() {
-        let (dx, dy) = DIRS[d];
+        let Point2 { x: dx, y: dy } = d.delta::<i32>();
         for _ in 0..s {
             x += dx;
             y += dy;
@@ -56,30 +68,28 @@ pub fn part_one(input: &str) -> usize {
     }
     let w = x1 - x0 + 1;
     let h = y1 - y0 + 1;
-    let mut grid: Vec<Vec<char>> = vec![vec!['.'; w as usize]; h as usize];
+    let mut grid = Grid::new(w as usize, h as usize, '.');
     for item in trench.iter_mut() {
         let (x, y) = ((item.0 - x0) as usize, (item.1 - y0) as usize);
-        grid[y][x] = '#';
+        grid[(x, y)] = '#';
     }
 
     let mut flood = |x: i32, y: i32| {
         let mut queue: Vec<(i32, i32)> = vec![(x, y)];
 
-        if grid[y as usize][x as usize] != '.' {
+        if grid.get(x, y) != Some(&'.') {
             return;
         }
-        grid[y as usize][x as usize] = '+';
+        grid[(x as usize, y as usize)] = '+';
 
         while let Some((x, y)) = queue.pop() {
-            for (dx, dy) in DIRS {
-                let (x, y) = (x + dx, y + dy);
-                if x < 0 || x >= w || y < 0 || y >= h {
-                    continue;
-                }
-                if grid[y as usize][x as usize] != '.' {
+            for dir in Dir::ALL {
+                let Point2 { x, y } = Point2::new(x, y).step(dir);
+                // out of bounds or not ground
+                let Some(tile @ '.') = grid.get_mut(x, y) else {
                     continue;
-                }
-                grid[y as usize][x as usize] = '+';
+                };
+                *tile = '+';
                 queue.push((x, y));
             }
         }
@@ -94,22 +104,34 @@ pub fn part_one(input: &str) -> usize {
         flood(0, y);
         flood(w - 1, y);
     }
-    grid.iter()
-        .map(|row| row.iter().filter(|&&ch| ch != '+').count())
-        .sum()
+    grid
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let plan = parse_input_hex(input);
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let plan = parse_input(input)?;
+    let grid = dig(&plan);
+    Ok(grid.find_all(|&ch| ch != '+').count())
+}
+
+// the trench and the lagoon of part one
+pub fn visualize(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let plan = parse_input(input)?;
+    Ok(dig(&plan).map(|&ch| match ch {
+        '#' => Tile::new('#', Color::Red),
+        '.' => Tile::new('~', Color::Blue),
+        _ => Tile::new('.', Color::Dim),
+    }))
+}
 
-    let mut vertices: Vec<(i64, i64)> = vec![(0, 0)];
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
+    let plan = parse_input_hex(input)?;
+
+    let mut vertices: Vec<Point2<i64>> = vec![Point2::default()];
     let mut boundary_count: i64 = 0;
-    let (mut x, mut y) = (0, 0);
+    let mut pos = Point2::default();
     for &(direction, count) in plan.iter() {
-        let (dx, dy) = DIRS[direction];
-        x += (dx * count) as i64;
-        y += (dy * count) as i64;
-        vertices.push((x, y));
+        pos += direction.delta() * count as i64;
+        vertices.push(pos);
         boundary_count += count as i64;
     }
 
@@ -136,14 +158,23 @@ pub fn part_two(input: &str) -> i64 {
     let area = vertices
         .windows(2)
         .map(|v| {
-            let ((x1, y1), (x2, y2)) = (v[0], v[1]);
+            let (Point2 { x: x1, y: y1 }, Point2 { x: x2, y: y2 }) =
+                (v[0], v[1]);
             (y1 + y2) * (x1 - x2) / 2
         })
         .sum::<i64>()
         .abs();
 
     let interior_count = area - boundary_count / 2 + 1;
-    interior_count + boundary_count
+    Ok(interior_count + boundary_count)
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> i64 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -156,5 +187,26 @@ mod tests {
         let input = read_example(18);
         assert_eq!(part_one(&input), 62);
         assert_eq!(part_two(&input), 952408144115);
+
+        let tiles = visualize(&input).unwrap();
+        assert_eq!(tiles.find_all(|t| t.color == Color::Red).count(), 38);
+        assert_eq!(tiles.find_all(|t| t.color != Color::Dim).count(), 62);
+    }
+
+    #[test]
+    fn malformed() {
+        let input = "R 6 (#70c710)\nX 5 (#0dc571)";
+        let err = try_part_one(input).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "X 5 (#0dc571)");
+        assert!(try_part_two(input).is_ok());
+
+        let err = try_part_two("R 6 (#70c71)").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        let err = try_part_two("R 6 (#70c714)").unwrap_err();
+        assert_eq!(
+            (err.column, err.reason.as_str()),
+            (12, "unknown direction")
+        );
     }
 }
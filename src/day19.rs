@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::ParseError;
+use crate::parse::{paragraphs, Line};
 
 type Value = i32;
 type Condition<'a> = (&'a str, &'a str, Value);
@@ -9,63 +12,139 @@ type RatingsPart<'a> = HashMap<&'a str, Value>;
 // (x, m, a, s)
 type RatingsRange = [(Value, Value); 4];
 
-fn parse_workflows(input: &str) -> Workflows {
-    fn parse_rules(input: &str) -> Vec<Rule> {
+fn parse_workflows<'a>(
+    lines: &[Line<'a>],
+) -> Result<Workflows<'a>, ParseError> {
+    fn parse_rules<'a>(
+        line: &Line<'a>,
+        input: &'a str,
+    ) -> Result<Vec<Rule<'a>>, ParseError> {
         // a<2006:qkq,m>2090:A,rfg
         input
             .trim()
             .split(',')
             .map(|rule| {
                 // a<2006:qkq
-                let s: Vec<&str> = rule.split(':').collect();
-                if s.len() == 1 {
-                    (None, s[0])
-                } else {
-                    (
-                        Some((
-                            &s[0][0..1], // var name
-                            &s[0][1..2], // compare '<' or '>'
-                            s[0][2..].parse::<Value>().unwrap(),
-                        )),
-                        s[1],
-                    )
+                let Some((condition, next)) = rule.split_once(':') else {
+                    return Ok((None, rule));
+                };
+                let var = condition.get(0..1).unwrap_or(condition);
+                if !["x", "m", "a", "s"].contains(&var) {
+                    return Err(line.error(var, "unknown category"));
+                }
+                let cmp = condition.get(1..2).unwrap_or_default();
+                if cmp != "<" && cmp != ">" {
+                    return Err(line.error(condition, "expected '<' or '>'"));
                 }
+                let value = line.parse::<Value>(&condition[2..])?;
+                Ok((Some((var, cmp, value)), next))
             })
             .collect()
     }
 
-    input
-        .trim()
-        .lines()
+    let workflows = lines
+        .iter()
         .map(|line| {
             // px{a<2006:qkq,m>2090:A,rfg}
-            let s: Vec<&str> = line.trim().split(&['{', '}'][..]).collect();
-            let name = s[0];
-            let rules = parse_rules(s[1]);
-            (name, rules)
+            let (name, rules) = line.split_once(line.text.trim(), "{")?;
+            let rules = rules
+                .strip_suffix('}')
+                .ok_or_else(|| line.error(line.text, "expected '}'"))?;
+            Ok((name, parse_rules(line, rules)?))
         })
-        .collect()
+        .collect::<Result<Workflows, ParseError>>()?;
+
+    if !workflows.contains_key("in") {
+        let line = lines.first().map_or(
+            Line {
+                number: 1,
+                text: "",
+            },
+            |l| *l,
+        );
+        return Err(line.error(line.text, "no workflow named \"in\""));
+    }
+    for line in lines {
+        let (name, _) = line.split_once(line.text.trim(), "{")?;
+        for (_, next) in workflows[name].iter() {
+            if !["A", "R"].contains(next) && !workflows.contains_key(next) {
+                return Err(line.error(next, "unknown workflow"));
+            }
+        }
+    }
+    let mut done = HashSet::new();
+    for line in lines {
+        let (name, _) = line.split_once(line.text.trim(), "{")?;
+        if leads_to_cycle(&workflows, name, &mut vec![], &mut done) {
+            return Err(line.error(name, "workflow leads into a cycle"));
+        }
+    }
+    Ok(workflows)
 }
 
-fn parse_parts(input: &str) -> Vec<RatingsPart> {
-    input
-        .trim()
-        .lines()
+// a part sent around a cycle of workflows is never accepted or rejected
+fn leads_to_cycle<'a>(
+    workflows: &Workflows<'a>,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> bool {
+    if done.contains(name) {
+        return false;
+    }
+    if path.contains(&name) {
+        return true;
+    }
+    path.push(name);
+    for &(_, next) in workflows[name].iter() {
+        if !["A", "R"].contains(&next)
+            && leads_to_cycle(workflows, next, path, done)
+        {
+            return true;
+        }
+    }
+    path.pop();
+    done.insert(name);
+    false
+}
+
+fn parse_parts<'a>(
+    lines: &[Line<'a>],
+) -> Result<Vec<RatingsPart<'a>>, ParseError> {
+    lines
+        .iter()
         .map(|line| {
             // {x=787,m=2655,a=1222,s=2876}
-            line.trim_matches(&['{', '}'])
+            let part = line
+                .text
+                .trim_matches(['{', '}'])
                 .split(',')
-                .map(|s| (&s[0..1], s[2..].parse::<Value>().unwrap()))
-                .collect::<Vec<(&str, Value)>>()
-                .into_iter()
-                .collect()
+                .map(|s| {
+                    let (var, value) = line.split_once(s, "=")?;
+                    Ok((var, line.parse::<Value>(value)?))
+                })
+                .collect::<Result<RatingsPart, ParseError>>()?;
+            match ["x", "m", "a", "s"].iter().find(|v| !part.contains_key(*v))
+            {
+                Some(var) => Err(line.error(
+                    line.text,
+                    &format!("missing category {:?}", var),
+                )),
+                None => Ok(part),
+            }
         })
         .collect()
 }
 
-fn parse_input(input: &str) -> (Workflows, Vec<RatingsPart>) {
-    let s: Vec<&str> = input.trim().split("\n\n").collect();
-    (parse_workflows(s[0]), parse_parts(s[1]))
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<(Workflows<'_>, Vec<RatingsPart<'_>>), ParseError> {
+    let sections = paragraphs(input);
+    let empty = vec![];
+    let workflows = parse_workflows(sections.first().unwrap_or(&empty))?;
+    let parts = parse_parts(sections.get(1).unwrap_or(&empty))?;
+    Ok((workflows, parts))
 }
 
 fn process(ratings: &RatingsPart, workflows: &Workflows) -> bool {
@@ -96,13 +175,14 @@ fn process(ratings: &RatingsPart, workflows: &Workflows) -> bool {
     panic!()
 }
 
-pub fn part_one(input: &str) -> Value {
-    let (workflows, parts) = parse_input(input);
-    parts
+pub fn try_part_one(input: &str) -> Result<Value, ParseError> {
+    let (workflows, parts) = parse_input(input)?;
+    let sum = parts
         .iter()
         .filter(|ratings| process(ratings, &workflows))
         .map(|ratings| ratings.values().sum::<Value>())
-        .sum()
+        .sum();
+    Ok(sum)
 }
 
 fn dfs(
@@ -145,8 +225,8 @@ fn dfs(
     }
 }
 
-pub fn part_two(input: &str) -> usize {
-    let (workflows, _) = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let (workflows, _) = parse_input(input)?;
     let mut ranges: Vec<RatingsRange> = vec![];
     dfs(
         &workflows,
@@ -154,7 +234,7 @@ pub fn part_two(input: &str) -> usize {
         [(1, 4000), (1, 4000), (1, 4000), (1, 4000)],
         &mut ranges,
     );
-    ranges
+    let combinations = ranges
         .into_iter()
         .map(|ratings| {
             ratings
@@ -163,7 +243,16 @@ pub fn part_two(input: &str) -> usize {
                 .map(|v| v as usize)
                 .product::<usize>()
         })
-        .sum()
+        .sum();
+    Ok(combinations)
+}
+
+pub fn part_one(input: &str) -> Value {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -177,4 +266,20 @@ mod tests {
         assert_eq!(part_one(&input), 19114);
         assert_eq!(part_two(&input), 167409079868000);
     }
+
+    #[test]
+    fn malformed() {
+        let err = try_part_one("in{q>1:A,R}\n\n{x=1}").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+        let err = try_part_one("in{x>1:px,R}\n\n{x=1}").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+        let err = try_part_one("in{x>1:A,R}\n\n{x=1,m=2}").unwrap_err();
+        assert_eq!(
+            (err.line, err.reason.as_str()),
+            (3, "missing category \"a\"")
+        );
+        assert!(try_part_two("in{x>1:A,R}").is_ok());
+        let err = try_part_two("in{x>1:px,R}\npx{in}").unwrap_err();
+        assert_eq!(err.reason, "workflow leads into a cycle");
+    }
 }
@@ -1,6 +1,10 @@
-use core::panic;
 use std::collections::{HashMap, VecDeque};
 
+use crate::error::ParseError;
+use crate::math;
+use crate::params::{self, ParamError};
+use crate::parse::lines;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum Pulse {
     Low,
@@ -15,7 +19,7 @@ enum ModuleType {
 }
 
 #[derive(Debug)]
-struct Module<'a> {
+pub(crate) struct Module<'a> {
     name: &'a str,
     mtype: ModuleType,
     outputs: Vec<&'a str>,
@@ -26,34 +30,67 @@ type ModuleMap<'a> = HashMap<&'a str, Module<'a>>;
 type FlagMap<'a> = HashMap<&'a str, bool>;
 type MemoryMap<'a> = HashMap<&'a str, HashMap<&'a str, Pulse>>;
 
-fn parse_input(input: &str) -> ModuleMap {
-    let mut modules: ModuleMap = input
-        .lines()
+// the button presses of part one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub presses: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { presses: 1000 }
+    }
+}
+
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["presses"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "presses" => self.presses = params::value(key, value)?,
+            _ => return Err(ParamError::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_input(input: &str) -> Result<ModuleMap<'_>, ParseError> {
+    let mut modules: ModuleMap = lines(input)
         .map(|line| {
-            if let Some((lhs, rhs)) = line.split_once("->") {
-                let outputs: Vec<&str> =
-                    rhs.split(',').map(|s| s.trim()).collect();
-                let mtype = match &lhs[..1] {
-                    "%" => ModuleType::FlipFlop,
-                    "&" => ModuleType::Conjunction,
-                    _ => ModuleType::Broadcaster,
-                };
-                let name = match mtype {
-                    ModuleType::Broadcaster => lhs.trim(),
-                    _ => lhs[1..].trim(),
-                };
-                Module {
-                    name,
-                    mtype,
-                    outputs,
-                    inputs: vec![],
-                }
-            } else {
-                panic!()
+            let (lhs, rhs) = line.split_once(line.text, "->")?;
+            let outputs: Vec<&str> =
+                rhs.split(',').map(|s| s.trim()).collect();
+            let mtype = match lhs.get(..1) {
+                Some("%") => ModuleType::FlipFlop,
+                Some("&") => ModuleType::Conjunction,
+                _ => ModuleType::Broadcaster,
+            };
+            let name = match mtype {
+                ModuleType::Broadcaster => lhs.trim(),
+                _ => lhs[1..].trim(),
+            };
+            if name.is_empty() {
+                return Err(line.error(lhs, "missing module name"));
+            }
+            // the button pulses it and nothing else remembers the button
+            if name == "broadcaster"
+                && !matches!(mtype, ModuleType::Broadcaster)
+            {
+                return Err(line.error(lhs, "broadcaster is not plain"));
             }
+            Ok(Module {
+                name,
+                mtype,
+                outputs,
+                inputs: vec![],
+            })
         })
-        .map(|module| (module.name, module))
-        .collect();
+        .map(|module| module.map(|module| (module.name, module)))
+        .collect::<Result<ModuleMap, ParseError>>()?;
+
+    if !modules.contains_key("broadcaster") {
+        return Err(ParseError::new(1, 1, "", "no broadcaster module"));
+    }
 
     modules
         .values()
@@ -71,7 +108,7 @@ fn parse_input(input: &str) -> ModuleMap {
             }
         });
 
-    modules
+    Ok(modules)
 }
 
 fn init_flags<'a>(modules: &'a ModuleMap) -> FlagMap<'a> {
@@ -152,15 +189,22 @@ fn press_button<OnPulse>(
     }
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let modules = parse_input(input);
+pub fn try_part_one(input: &str) -> Result<u64, ParseError> {
+    try_part_one_with(input, &Params::default())
+}
+
+pub fn try_part_one_with(
+    input: &str,
+    params: &Params,
+) -> Result<u64, ParseError> {
+    let modules = parse_input(input)?;
     let mut flags = init_flags(&modules);
     let mut memories = init_memories(&modules);
 
     let mut lo = 0;
     let mut hi = 0;
 
-    for _ in 0..1000 {
+    for _ in 0..params.presses {
         let mut on_pulse = |_: &str, pulse: Pulse, _: &str| match pulse {
             Pulse::Low => lo += 1,
             Pulse::High => hi += 1,
@@ -168,65 +212,98 @@ pub fn part_one(input: &str) -> u32 {
         press_button(&modules, &mut flags, &mut memories, &mut on_pulse);
     }
 
-    lo * hi
+    Ok(lo * hi)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let modules = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<u64, ParseError> {
+    let modules = parse_input(input)?;
     let mut flags = init_flags(&modules);
     let mut memories = init_memories(&modules);
 
-    let feed: &str = modules
+    let Some(feed) = modules
         .values()
         .filter(|m| m.outputs.contains(&"rx"))
         .map(|m| m.name)
         .next()
-        .unwrap();
+    else {
+        return Err(ParseError::new(1, 1, "", "no module sends to rx"));
+    };
 
-    let mut feeds: HashMap<&str, usize> = modules
+    // the first two presses each feed gets a low pulse on
+    let mut feeds: HashMap<&str, Vec<u64>> = modules
         .values()
         .filter(|m| m.outputs.contains(&feed))
-        .map(|m| (m.name, 0))
+        .map(|m| (m.name, vec![]))
         .collect();
 
+    // the flip-flops are all the state there is between presses, so it
+    // loops within 2^n + 1 presses and a feed without a low pulse twice
+    // in twice that never gets one. the real counters have 12 bits, so a
+    // million presses are plenty either way.
+    let flip_flops = init_flags(&modules).len() as u32;
+    let states = 1u64.checked_shl(flip_flops).unwrap_or(u64::MAX);
+    let limit = states.saturating_add(1).saturating_mul(2).min(1 << 20);
+
     let mut pressed = 0;
 
-    while feeds.values().any(|v| *v == 0) {
+    while feeds.values().any(|v| v.len() < 2) {
+        if pressed == limit {
+            let reason = "a feed never gets a low pulse";
+            return Err(ParseError::new(1, 1, "", reason));
+        }
         pressed += 1;
         let mut on_pulse = |_: &str, pulse: Pulse, name: &str| {
             if pulse == Pulse::Low {
-                if let Some(cycle) = feeds.get_mut(name) {
-                    *cycle = pressed
+                if let Some(presses) = feeds.get_mut(name) {
+                    if presses.last() != Some(&pressed) {
+                        presses.push(pressed);
+                    }
                 }
             }
         };
         press_button(&modules, &mut flags, &mut memories, &mut on_pulse);
     }
 
-    fn lcm(nums: &[usize]) -> usize {
-        if nums.len() == 1 {
-            return nums[0];
-        }
-        let a = nums[0];
-        let b = lcm(&nums[1..]);
-        a * b / gcd_of_two_numbers(a, b)
+    // (offset, period) of every feed
+    let cycles: Vec<(u64, u64)> =
+        feeds.values().map(|v| (v[0], v[1] - v[0])).collect();
+    math::first_common(&cycles)
+        .ok_or_else(|| ParseError::new(1, 1, "", "the feeds never line up"))
+}
+
+// presses the button until rx gets a low pulse, however long it takes
+pub fn reference_part_two(input: &str) -> Result<u64, ParseError> {
+    let modules = parse_input(input)?;
+    if !modules.values().any(|m| m.outputs.contains(&"rx")) {
+        return Err(ParseError::new(1, 1, "", "no module sends to rx"));
     }
+    let mut flags = init_flags(&modules);
+    let mut memories = init_memories(&modules);
 
-    fn gcd_of_two_numbers(a: usize, b: usize) -> usize {
-        if b == 0 {
-            return a;
-        }
-        gcd_of_two_numbers(b, a % b)
+    let mut pressed = 0;
+    let mut done = false;
+    while !done {
+        pressed += 1;
+        let mut on_pulse = |_: &str, pulse: Pulse, name: &str| {
+            done |= pulse == Pulse::Low && name == "rx";
+        };
+        press_button(&modules, &mut flags, &mut memories, &mut on_pulse);
     }
+    Ok(pressed)
+}
 
-    let cycles: Vec<usize> = feeds.values().copied().collect();
-    lcm(&cycles)
+pub fn part_one(input: &str) -> u64 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u64 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
+    use crate::{read_example, read_example_n};
 
     #[test]
     fn example() {
@@ -236,11 +313,22 @@ mod tests {
 
     #[test]
     fn example2() {
-        let input = "broadcaster -> a\n\
-            %a -> inv, con\n\
-            &inv -> b\n\
-            %b -> con\n\
-            &con -> output";
-        assert_eq!(part_one(input), 11687500);
+        let input = read_example_n(20, 2);
+        assert_eq!(part_one(&input), 11687500);
+    }
+
+    #[test]
+    fn malformed() {
+        let err = try_part_one("broadcaster -> a\n%a => b").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "%a => b"));
+        assert_eq!(err.reason, "expected \"->\"");
+        let err = try_part_one("broadcaster -> a\n% -> b").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(try_part_two(&read_example(20)).is_err());
+        let err = try_part_one("&broadcaster -> a").unwrap_err();
+        assert_eq!(err.reason, "broadcaster is not plain");
+        let input = "broadcaster -> b\n%b -> c\n&c -> rx\n&d -> c";
+        let err = try_part_two(input).unwrap_err();
+        assert_eq!(err.reason, "a feed never gets a low pulse");
     }
 }
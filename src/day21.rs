@@ -1,72 +1,96 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::collections::VecDeque;
 
-struct Garden {
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::params::{self, ParamError};
+use crate::parse::rectangle;
+use crate::search;
+use crate::visualize::{Color, Tile};
+
+pub(crate) struct Garden {
     start: (i32, i32),
-    rocks: HashSet<(i32, i32)>,
-    w: i32,
-    h: i32,
-}
-
-fn parse_input(input: &str) -> Garden {
-    let mut start: (i32, i32) = (0, 0);
-    let mut rocks: HashSet<(i32, i32)> = HashSet::new();
-    let mut w: i32 = 0;
-    let mut h: i32 = 0;
-    input
-        .lines()
-        .enumerate()
-        .map(|(x, s)| (x as i32, s))
-        .for_each(|(x, s)| {
-            s.chars().enumerate().map(|(y, c)| (y as i32, c)).for_each(
-                |(y, c)| {
-                    match c {
-                        '#' => {
-                            rocks.insert((x, y));
-                        }
-                        'S' => {
-                            start = (x, y);
-                        }
-                        _ => {}
-                    };
-                    h = h.max(y)
-                },
-            );
-            w = w.max(x)
-        });
-    assert!(w == h);
-    w += 1;
-    h += 1;
-    Garden { start, rocks, w, h }
+    // true for the rocks
+    rocks: Grid<bool>,
 }
 
-fn count_plots(garden: &Garden, steps: usize) -> Vec<(i32, i32)> {
-    let mut plots: Vec<(i32, i32)> = Vec::new();
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut queue: VecDeque<(usize, (i32, i32))> = VecDeque::new();
-    queue.push_back((steps, garden.start));
-    while let Some((step, (x, y))) = queue.pop_front() {
-        if !visited.insert((x, y)) {
-            continue;
-        }
-        if step % 2 == 0 {
-            plots.push((x, y));
-        }
-        if step == 0 {
-            continue;
+impl Garden {
+    fn width(&self) -> i32 {
+        self.rocks.width() as i32
+    }
+
+    fn height(&self) -> i32 {
+        self.rocks.height() as i32
+    }
+}
+
+// the steps taken in each part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub steps_one: usize,
+    pub steps_two: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            steps_one: 64,
+            steps_two: 26501365,
         }
-        for (x, y) in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
-            let cx = ((x % garden.w) + (garden.w)) % garden.w;
-            let cy = ((y % garden.h) + (garden.h)) % garden.h;
-            if garden.rocks.contains(&(cx, cy)) {
-                continue;
-            }
-            queue.push_back((step - 1, (x, y)));
+    }
+}
+
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["steps_one", "steps_two"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "steps_one" => self.steps_one = params::value(key, value)?,
+            "steps_two" => self.steps_two = params::value(key, value)?,
+            _ => return Err(ParamError::unknown(key)),
         }
+        Ok(())
     }
+}
 
-    plots
+pub(crate) fn parse_input(input: &str) -> Result<Garden, ParseError> {
+    let rows = rectangle(input)?;
+    if rows.len() != rows[0].text.len() {
+        return Err(rows[0].error(rows[0].text, "garden is not square"));
+    }
+    let tiles = Grid::from_lines(&rows, |c| match c {
+        '#' | 'S' | '.' => Ok(c),
+        _ => Err("unknown tile"),
+    })?;
+    let mut starts = tiles.find_all(|&c| c == 'S');
+    let Some((x, y)) = starts.next() else {
+        return Err(rows[0].error(rows[0].text, "no start"));
+    };
+    if let Some((x, y)) = starts.next() {
+        return Err(rows[y].error(&rows[y].text[x..], "more than one start"));
+    }
+    Ok(Garden {
+        start: (x as i32, y as i32),
+        rocks: tiles.map(|&c| c == '#'),
+    })
+}
+
+// the plots reachable in exactly `steps`, on the garden repeated forever
+fn count_plots(garden: &Garden, steps: usize) -> Vec<(i32, i32)> {
+    let neighbours = |&(x, y): &(i32, i32)| {
+        [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)]
+            .into_iter()
+            .filter(|&(x, y)| {
+                let cx = x.rem_euclid(garden.width()) as usize;
+                let cy = y.rem_euclid(garden.height()) as usize;
+                !garden.rocks[(cx, cy)]
+            })
+    };
+    // a plot reached earlier can be stepped off and back on
+    search::bfs_iter(garden.start, neighbours)
+        .take_while(|&(_, depth)| depth <= steps)
+        .filter(|&(_, depth)| depth % 2 == steps % 2)
+        .map(|(pos, _)| pos)
+        .collect()
 }
 
 fn _part_one(garden: &Garden, steps: usize) -> usize {
@@ -74,7 +98,7 @@ fn _part_one(garden: &Garden, steps: usize) -> usize {
 }
 
 fn _part_two(garden: &Garden, steps: usize) -> usize {
-    let w = garden.w as usize;
+    let w = garden.width() as usize;
     let d = steps / w;
     let m = steps % w;
     let v = 5 + d as i32 % 2;
@@ -83,23 +107,20 @@ fn _part_two(garden: &Garden, steps: usize) -> usize {
     if steps <= s {
         return plots.len();
     }
-    #[cfg(debug_assertions)]
-    println!("==== {} -> steps={}, div={}, mod={}", steps, s, d, m);
-
     let mut boxes: HashMap<(i32, i32), usize> = HashMap::new();
     plots
         .iter()
         .map(|(x, y)| {
             (
                 if x < &0 {
-                    (x + 1) / garden.w - 1
+                    (x + 1) / garden.width() - 1
                 } else {
-                    x / garden.w
+                    x / garden.width()
                 },
                 if y < &0 {
-                    (y + 1) / garden.h - 1
+                    (y + 1) / garden.height() - 1
                 } else {
-                    y / garden.h
+                    y / garden.height()
                 },
             )
         })
@@ -111,14 +132,6 @@ fn _part_two(garden: &Garden, steps: usize) -> usize {
             }
         });
 
-    #[cfg(debug_assertions)]
-    for j in -v..=v {
-        for i in -v..=v {
-            print!("{:5}, ", boxes.get(&(i, j)).unwrap_or(&0));
-        }
-        println!();
-    }
-
     let count = |i, j| boxes.get(&(i, j)).unwrap_or(&0);
     let mut answer = 0;
     {
@@ -144,12 +157,6 @@ fn _part_two(garden: &Garden, steps: usize) -> usize {
         let se = count(1, v - 1 - i);
         let nw = count(-1, -v + 1 + i);
         let sw = count(-1, v - 1 - i);
-        #[cfg(debug_assertions)]
-        println!(
-            "{}: N={}, S={}, E={}, W={}, NE={}, SE={}, NW={}, SW={}",
-            i, n, s, e, w, ne, se, nw, sw
-        );
-
         answer += n + s + e + w;
         let c = d - i as usize;
         answer += ne * c;
@@ -161,14 +168,63 @@ fn _part_two(garden: &Garden, steps: usize) -> usize {
     answer
 }
 
+// the plots reachable in the steps of part one
+pub fn visualize(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let garden = parse_input(input)?;
+    let mut tiles = garden.rocks.map(|&rock| match rock {
+        true => Tile::new('#', Color::Plain),
+        false => Tile::new('.', Color::Dim),
+    });
+    for (x, y) in count_plots(&garden, Params::default().steps_one) {
+        if let Some(tile) = tiles.get_mut(x, y) {
+            *tile = Tile::new('O', Color::Green);
+        }
+    }
+    let (x, y) = garden.start;
+    tiles[(x as usize, y as usize)].color = Color::Red;
+    Ok(tiles)
+}
+
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    try_part_one_with(input, &Params::default())
+}
+
+pub fn try_part_one_with(
+    input: &str,
+    params: &Params,
+) -> Result<usize, ParseError> {
+    let garden = parse_input(input)?;
+    Ok(_part_one(&garden, params.steps_one))
+}
+
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    try_part_two_with(input, &Params::default())
+}
+
+pub fn try_part_two_with(
+    input: &str,
+    params: &Params,
+) -> Result<usize, ParseError> {
+    let garden = parse_input(input)?;
+    Ok(_part_two(&garden, params.steps_two))
+}
+
+// walks all the steps of part two instead of extrapolating from the
+// gardens around the start
+pub fn reference_part_two_with(
+    input: &str,
+    params: &Params,
+) -> Result<usize, ParseError> {
+    let garden = parse_input(input)?;
+    Ok(_part_one(&garden, params.steps_two))
+}
+
 pub fn part_one(input: &str) -> usize {
-    let garden = parse_input(input);
-    _part_one(&garden, 64)
+    try_part_one(input).unwrap()
 }
 
 pub fn part_two(input: &str) -> usize {
-    let garden = parse_input(input);
-    _part_two(&garden, 26501365)
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -179,8 +235,13 @@ mod tests {
     #[test]
     fn example() {
         let input = read_example(21);
-        let garden = parse_input(&input);
+        let garden = parse_input(&input).unwrap();
         assert_eq!(_part_one(&garden, 6), 16);
+        let params = Params {
+            steps_one: 6,
+            ..Params::default()
+        };
+        assert_eq!(try_part_one_with(&input, &params), Ok(16));
         assert_eq!(_part_two(&garden, 6), 16);
         assert_eq!(_part_two(&garden, 10), 50);
         assert_eq!(_part_two(&garden, 50), 1594);
@@ -1,28 +1,20 @@
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
-#[derive(Debug, Clone)]
-struct Point3D {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl Point3D {
-    fn new(x: i32, y: i32, z: i32) -> Self {
-        Point3D { x, y, z }
-    }
-}
+use crate::error::ParseError;
+use crate::geom::Point3;
+use crate::parse::lines;
+use crate::profile;
 
 #[derive(Debug, Clone)]
-struct Brick {
+pub(crate) struct Brick {
     i: usize,
-    a: Point3D,
-    b: Point3D,
+    a: Point3<i32>,
+    b: Point3<i32>,
 }
 
 impl Brick {
-    fn new(i: usize, a: Point3D, b: Point3D) -> Self {
+    fn new(i: usize, a: Point3<i32>, b: Point3<i32>) -> Self {
         assert!(a.x <= b.x && a.y <= b.y && a.z <= b.z);
         assert!(
             (a.x == b.x && (a.y == b.y || a.z == b.z))
@@ -44,21 +36,33 @@ impl Brick {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Brick> {
-    input
-        .lines()
+pub(crate) fn parse_input(input: &str) -> Result<Vec<Brick>, ParseError> {
+    lines(input)
         .enumerate()
-        .map(|(i, s)| {
-            let parts: Vec<Vec<i32>> = s
-                .splitn(2, '~')
+        .map(|(i, line)| {
+            let (a, b) = line.split_once(line.text, "~")?;
+            let parts = [a, b]
+                .iter()
                 .map(|s| {
-                    s.splitn(3, ',').map(|v| v.parse().unwrap()).collect()
+                    let v = s
+                        .split(',')
+                        .map(|v| line.parse::<i32>(v))
+                        .collect::<Result<Vec<i32>, ParseError>>()?;
+                    match v[..] {
+                        [x, y, z] => Ok(Point3::new(x, y, z)),
+                        _ => Err(line.error(s, "expected x,y,z")),
+                    }
                 })
-                .collect();
-            let a = Point3D::new(parts[0][0], parts[0][1], parts[0][2]);
-            let b = Point3D::new(parts[1][0], parts[1][1], parts[1][2]);
-
-            Brick::new(i, a, b)
+                .collect::<Result<Vec<Point3<i32>>, ParseError>>()?;
+            let (a, b) = (parts[0], parts[1]);
+            let differs = [a.x != b.x, a.y != b.y, a.z != b.z];
+            if a.x > b.x || a.y > b.y || a.z > b.z {
+                return Err(line.error(line.text, "ends are not ordered"));
+            }
+            if differs.iter().filter(|&&d| d).count() > 1 {
+                return Err(line.error(line.text, "brick is not straight"));
+            }
+            Ok(Brick::new(i, a, b))
         })
         .collect()
 }
@@ -92,20 +96,20 @@ fn fall(bricks: &mut Vec<Brick>) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
     (supported, supporting)
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut bricks = parse_input(input);
-    let (supported, _) = fall(&mut bricks);
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let mut bricks = profile::time("parse", || parse_input(input))?;
+    let (supported, _) = profile::time("fall", || fall(&mut bricks));
     let set: HashSet<usize> = supported
         .iter()
         .filter(|v| v.len() == 1)
         .map(|v| v[0])
         .collect();
-    bricks.len() - set.len()
+    Ok(bricks.len() - set.len())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let mut bricks = parse_input(input);
-    let (supported, supporting) = fall(&mut bricks);
+pub fn try_part_two(input: &str) -> Result<usize, ParseError> {
+    let mut bricks = profile::time("parse", || parse_input(input))?;
+    let (supported, supporting) = profile::time("fall", || fall(&mut bricks));
 
     let disintegrate = |i: usize| -> usize {
         let mut distintegrated: HashSet<usize> = HashSet::new();
@@ -125,19 +129,29 @@ pub fn part_two(input: &str) -> usize {
         distintegrated.len() - 1
     };
 
-    bricks.iter().map(|brick| disintegrate(brick.i)).sum()
+    let _span = profile::span("disintegrate");
+    Ok(bricks.iter().map(|brick| disintegrate(brick.i)).sum())
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> usize {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, vec};
     use crate::read_example;
 
     #[test]
     fn test_encountered() {
-        let b1 = Brick::new(1, Point3D::new(1, 0, 0), Point3D::new(1, 2, 0));
-        let b2 = Brick::new(2, Point3D::new(0, 0, 2), Point3D::new(2, 0, 2));
-        let b3 = Brick::new(3, Point3D::new(0, 0, 0), Point3D::new(2, 0, 0));
+        let b1 = Brick::new(1, Point3::new(1, 0, 0), Point3::new(1, 2, 0));
+        let b2 = Brick::new(2, Point3::new(0, 0, 2), Point3::new(2, 0, 2));
+        let b3 = Brick::new(3, Point3::new(0, 0, 0), Point3::new(2, 0, 0));
         assert!(!b1.is_encountered(&b2));
         assert!(b1.is_encountered(&b3));
     }
@@ -148,4 +162,46 @@ mod tests {
         assert_eq!(part_one(&input), 5);
         assert_eq!(part_two(&input), 7);
     }
+
+    #[test]
+    fn settled() {
+        // x, y, z, axis and length of each brick
+        let strategy = vec((0..=3, 0..=3, 1..=12, 0..=2usize, 0..=3), 0..=8);
+        let overlap = |bricks: &[Brick]| {
+            bricks.iter().enumerate().any(|(i, a)| {
+                bricks[i + 1..].iter().any(|b| a.is_encountered(b))
+            })
+        };
+        check(strategy, |specs| {
+            let mut bricks: Vec<Brick> = specs
+                .iter()
+                .enumerate()
+                .map(|(i, &(x, y, z, axis, len))| {
+                    let a = Point3::new(x, y, z);
+                    let mut b = a;
+                    match axis {
+                        0 => b.x += len,
+                        1 => b.y += len,
+                        _ => b.z += len,
+                    }
+                    Brick::new(i, a, b)
+                })
+                .collect();
+            // not a snapshot of falling bricks
+            if overlap(&bricks) {
+                return true;
+            }
+            fall(&mut bricks);
+            // and each one lies on the ground or on another
+            let resting = |brick: &Brick| {
+                let mut lower = brick.clone();
+                lower.set_z(brick.a.z - 1);
+                brick.a.z == 1
+                    || bricks.iter().any(|other| {
+                        other.i != brick.i && lower.is_encountered(other)
+                    })
+            };
+            !overlap(&bricks) && bricks.iter().all(resting)
+        });
+    }
 }
@@ -1,111 +1,111 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-type Pos = (i16, i16);
-type Trails = HashMap<Pos, u8>;
+use crate::error::ParseError;
+use crate::geom::{Dir, Point2};
+use crate::grid::Grid;
+use crate::parse::rectangle;
+use crate::profile;
+
+type Pos = Point2<i16>;
+type Trails = Grid<u8>;
 type Map = HashMap<Pos, Vec<(Pos, u16)>>;
 
-fn parse_input(input: &str) -> (Trails, i16, i16) {
-    let trails: Trails = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, s)| {
-            s.bytes()
-                .enumerate()
-                .filter(|(_, tile)| tile != &b'#')
-                .map(|(x, tile)| ((x as i16, y as i16), tile))
-                .collect::<Vec<(Pos, u8)>>()
-        })
-        .collect();
-    let height = input.lines().count() as i16;
-    let start = input.find('.').unwrap() as i16;
-
-    (trails, height, start)
+pub(crate) fn parse_input(
+    input: &str,
+) -> Result<(Trails, i16, i16), ParseError> {
+    let rows = rectangle(input)?;
+    let trails = Grid::from_lines(&rows, |c| match c {
+        '#' | '.' | '>' | 'v' | '<' | '^' => Ok(c as u8),
+        _ => Err("unknown tile"),
+    })?;
+    let height = trails.height() as i16;
+    let Some(start) = trails.row(0).iter().position(|&t| t == b'.') else {
+        return Err(rows[0].error(rows[0].text, "no start"));
+    };
+
+    Ok((trails, height, start as i16))
 }
 
-pub fn part_one(input: &str) -> u16 {
+fn is_trail(trails: &Trails, pos: Pos) -> bool {
+    trails.get(pos.x, pos.y).is_some_and(|&t| t != b'#')
+}
+
+pub fn try_part_one(input: &str) -> Result<u16, ParseError> {
     fn dfs(
         trails: &Trails,
         visited: &mut HashSet<Pos>,
         max_steps: &mut u16,
         height: i16,
-        x: i16,
-        y: i16,
+        pos: Pos,
         steps: u16,
     ) {
-        if y == height - 1 {
+        if pos.y == height - 1 {
             *max_steps = steps.max(*max_steps);
             return;
         }
-        const DIRS: [Pos; 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-        let dirs = match trails.get(&(x, y)) {
-            Some(b'.') => &DIRS,
-            Some(b'>') => &DIRS[0..1],
-            Some(b'v') => &DIRS[1..2],
-            Some(b'<') => &DIRS[2..3],
-            Some(b'^') => &DIRS[3..4],
-            _ => {
-                panic!();
-            }
+        let dirs = match trails.get(pos.x, pos.y) {
+            Some(b'.') => &Dir::ALL[..],
+            // a slope only goes downhill
+            Some(&t) => &[Dir::from_char(t as char).unwrap()],
+            None => panic!(),
         };
-        for (dx, dy) in dirs {
-            let (x, y) = (x + dx, y + dy);
-            if trails.contains_key(&(x, y)) && visited.insert((x, y)) {
-                dfs(trails, visited, max_steps, height, x, y, steps + 1);
-                visited.remove(&(x, y));
+        for &dir in dirs {
+            let next = pos.step(dir);
+            if is_trail(trails, next) && visited.insert(next) {
+                dfs(trails, visited, max_steps, height, next, steps + 1);
+                visited.remove(&next);
             }
         }
     }
 
-    let (trails, height, start) = parse_input(input);
-    let mut visited: HashSet<Pos> = HashSet::from([(start, 0)]);
+    let (trails, height, start) =
+        profile::time("parse", || parse_input(input))?;
+    let start = Point2::new(start, 0);
+    let mut visited: HashSet<Pos> = HashSet::from([start]);
     let mut max_steps = 0;
-    dfs(&trails, &mut visited, &mut max_steps, height, start, 0, 0);
-    max_steps
+    profile::time("dfs", || {
+        dfs(&trails, &mut visited, &mut max_steps, height, start, 0)
+    });
+    Ok(max_steps)
 }
 
-pub fn part_two(input: &str) -> u16 {
+pub fn try_part_two(input: &str) -> Result<u16, ParseError> {
     fn compress_map(trails: &Trails) -> Map {
         let mut map: Map = HashMap::new();
-        const DIRS: [Pos; 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
 
-        let nodes: HashMap<Pos, Vec<(i16, i16)>> = trails
-            .keys()
+        let nodes: HashMap<Pos, Vec<Pos>> = trails
+            .find_all(|&t| t != b'#')
             .map(|(x, y)| {
-                let nexts: Vec<(i16, i16)> = DIRS
-                    .iter()
-                    .map(|(dx, dy)| (x + dx, y + dy))
-                    .filter(|k| trails.contains_key(k))
+                let pos = Point2::new(x as i16, y as i16);
+                let nexts: Vec<Pos> = pos
+                    .neighbours4()
+                    .into_iter()
+                    .filter(|&k| is_trail(trails, k))
                     .collect();
-                ((*x, *y), nexts)
+                (pos, nexts)
             })
             .filter(|(_, dirs)| dirs.len() != 2)
             .collect();
 
         for (pos, nexts) in nodes.iter() {
             for next in nexts {
-                let (mut x0, mut y0) = pos;
-                let (mut x1, mut y1) = next;
+                let mut p0 = *pos;
+                let mut p1 = *next;
                 let mut steps = 1;
-                loop {
-                    steps += 1;
-                    let (x, y) = DIRS
-                        .iter()
-                        .map(|(dx, dy)| (dx + x1, dy + y1))
-                        .filter(|k| k != &(x0, y0))
-                        .find(|k| trails.contains_key(k))
+                // a tile that is not a node has a trail on two sides
+                while !nodes.contains_key(&p1) {
+                    let p = p1
+                        .neighbours4()
+                        .into_iter()
+                        .filter(|k| k != &p0)
+                        .find(|&k| is_trail(trails, k))
                         .unwrap();
-                    if nodes.contains_key(&(x, y)) {
-                        if let Some(v) = map.get_mut(pos) {
-                            v.push(((x, y), steps));
-                        } else {
-                            map.insert(*pos, vec![((x, y), steps)]);
-                        }
-                        break;
-                    }
-                    (x0, y0) = (x1, y1);
-                    (x1, y1) = (x, y);
+                    p0 = p1;
+                    p1 = p;
+                    steps += 1;
                 }
+                map.entry(*pos).or_default().push((p1, steps));
             }
         }
         map
@@ -113,32 +113,43 @@ pub fn part_two(input: &str) -> u16 {
 
     fn dfs(
         map: &Map,
-        visited: &mut HashSet<(i16, i16)>,
+        visited: &mut HashSet<Pos>,
         max_steps: &mut u16,
         height: i16,
-        x: i16,
-        y: i16,
+        pos: Pos,
         steps: u16,
     ) {
-        if y == height - 1 {
+        if pos.y == height - 1 {
             *max_steps = steps.max(*max_steps);
             // println!("{:?}", (steps, max_steps));
             return;
         }
-        for (p, s) in map.get(&(x, y)).unwrap() {
+        for (p, s) in map.get(&pos).into_iter().flatten() {
             if visited.insert(*p) {
-                dfs(map, visited, max_steps, height, p.0, p.1, steps + s);
+                dfs(map, visited, max_steps, height, *p, steps + s);
                 visited.remove(p);
             }
         }
     }
 
-    let (trails, height, start) = parse_input(input);
-    let map = compress_map(&trails);
-    let mut visited: HashSet<Pos> = HashSet::from([(start, 0)]);
+    let (trails, height, start) =
+        profile::time("parse", || parse_input(input))?;
+    let map = profile::time("compress_map", || compress_map(&trails));
+    let start = Point2::new(start, 0);
+    let mut visited: HashSet<Pos> = HashSet::from([start]);
     let mut max_steps = 0;
-    dfs(&map, &mut visited, &mut max_steps, height, start, 0, 0);
-    max_steps
+    profile::time("dfs", || {
+        dfs(&map, &mut visited, &mut max_steps, height, start, 0)
+    });
+    Ok(max_steps)
+}
+
+pub fn part_one(input: &str) -> u16 {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> u16 {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -151,5 +162,7 @@ mod tests {
         let input = read_example(23);
         assert_eq!(part_one(&input), 94);
         assert_eq!(part_two(&input), 154);
+        // the start leads straight to the end
+        assert_eq!(part_two("#.#\n#.#"), 1);
     }
 }
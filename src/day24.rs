@@ -1,8 +1,33 @@
 use std::fmt::Debug;
 use std::str::FromStr;
 
+use crate::error::ParseError;
+use crate::params::{self, ParamError};
+use crate::parse::{lines, Line};
+
+// The test area of part one. An unset bound is guessed from the number
+// of hailstones: 7 to 27 for the example, 2e14 to 4e14 for an input.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl params::Params for Params {
+    const KEYS: &'static [&'static str] = &["min", "max"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "min" => self.min = Some(params::value(key, value)?),
+            "max" => self.max = Some(params::value(key, value)?),
+            _ => return Err(ParamError::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-struct Ray<T> {
+pub(crate) struct Ray<T> {
     px: T,
     py: T,
     pz: T,
@@ -11,32 +36,41 @@ struct Ray<T> {
     vz: T,
 }
 
-fn parse_input<T>(input: &str) -> Vec<Ray<T>>
+pub(crate) fn parse_input<T>(input: &str) -> Result<Vec<Ray<T>>, ParseError>
 where
     T: FromStr + Copy,
-    <T as FromStr>::Err: Debug,
 {
-    input
-        .lines()
-        .map(|s| {
-            s.split([',', '@'])
+    lines(input)
+        .map(|line| {
+            let v = line
+                .text
+                .split([',', '@'])
                 .map(|s| s.trim())
-                .map(|s| s.parse::<T>().unwrap())
-                .collect::<Vec<T>>()
-        })
-        .inspect(|v| assert_eq!(6, v.len()))
-        .map(|v| Ray {
-            px: v[0],
-            py: v[1],
-            pz: v[2],
-            vx: v[3],
-            vy: v[4],
-            vz: v[5],
+                .map(|s| line.parse::<T>(s))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            if v.len() != 6 {
+                return Err(line.error(line.text, "expected six numbers"));
+            }
+            Ok(Ray {
+                px: v[0],
+                py: v[1],
+                pz: v[2],
+                vx: v[3],
+                vy: v[4],
+                vz: v[5],
+            })
         })
         .collect()
 }
 
-pub fn part_one(input: &str) -> usize {
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    try_part_one_with(input, &Params::default())
+}
+
+pub fn try_part_one_with(
+    input: &str,
+    params: &Params,
+) -> Result<usize, ParseError> {
     fn general_equation(a: &Ray<f32>) -> (f32, f32, f32) {
         (a.vy, -a.vx, a.vx * a.py - a.vy * a.px)
     }
@@ -55,13 +89,15 @@ pub fn part_one(input: &str) -> usize {
     }
 
     let mut answer = 0;
-    let rays = parse_input(input);
+    let rays = parse_input(input)?;
     let (min, max) = if rays.len() <= 5 {
         (7.0, 27.0)
     } else {
         (200000000000000.0, 400000000000000.0)
     };
-    for (i, a) in rays.iter().enumerate().take(rays.len() - 1) {
+    let min = params.min.map_or(min, |min| min as f32);
+    let max = params.max.map_or(max, |max| max as f32);
+    for (i, a) in rays.iter().enumerate().take(rays.len().saturating_sub(1)) {
         for b in rays.iter().skip(i + 1) {
             if let Some((x, y)) = get_intersection(a, b) {
                 if (x >= min && x <= max && y >= min && y <= max)
@@ -75,7 +111,7 @@ pub fn part_one(input: &str) -> usize {
             }
         }
     }
-    answer
+    Ok(answer)
 }
 
 fn gaussian_elimination(matrix: &[Vec<f64>]) -> Vec<f64> {
@@ -100,6 +136,7 @@ fn gaussian_elimination(matrix: &[Vec<f64>]) -> Vec<f64> {
                 continue;
             }
             let c = a / b;
+            #[allow(clippy::needless_range_loop)]
             for k in 0..w {
                 m[j][k] = m[i][k] - m[j][k] * c;
             }
@@ -118,8 +155,17 @@ fn gaussian_elimination(matrix: &[Vec<f64>]) -> Vec<f64> {
     answer
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let rays: Vec<Ray<f64>> = parse_input(input);
+pub fn try_part_two(input: &str) -> Result<i64, ParseError> {
+    let rays: Vec<Ray<f64>> = parse_input(input)?;
+    if rays.len() < 5 {
+        let line = lines(input).last().unwrap_or(Line {
+            number: 1,
+            text: "",
+        });
+        return Err(
+            line.error(line.text, "expected at least five hailstones")
+        );
+    }
 
     let xy: Vec<Vec<f64>> = rays
         .windows(2)
@@ -174,10 +220,18 @@ pub fn part_two(input: &str) -> i64 {
     assert!(a1[1] == a3[0]);
     assert!(a2[1] == a3[1]);
 
-    a1[0] as i64 + a1[1] as i64 + a2[1] as i64
+    Ok(a1[0] as i64 + a1[1] as i64 + a2[1] as i64)
     // 194723518367339 + 181910661443432 + 150675954587450
 }
 
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
+}
+
+pub fn part_two(input: &str) -> i64 {
+    try_part_two(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
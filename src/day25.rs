@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
+use crate::error::ParseError;
+use crate::parse::lines;
+
 // Define type aliases for readability
 type Graph<'a> = HashMap<&'a str, HashMap<&'a str, i32>>;
 
@@ -70,7 +73,7 @@ fn edmonds_karp<'a>(
     max_flow
 }
 
-fn parse_input(input: &str) -> Graph {
+pub(crate) fn parse_input(input: &str) -> Result<Graph<'_>, ParseError> {
     let mut map = Graph::new();
     let mut add_item = |a, b| {
         if let Some(item) = map.get_mut(a) {
@@ -81,15 +84,21 @@ fn parse_input(input: &str) -> Graph {
             map.insert(a, item);
         }
     };
-    input.lines().for_each(|line| {
-        let mut iter = line.splitn(2, ':');
-        let u = iter.next().unwrap().trim();
-        iter.next().unwrap().split_whitespace().for_each(|v| {
+    for line in lines(input) {
+        let (u, vs) = line.split_once(line.text, ":")?;
+        let u = u.trim();
+        if u.is_empty() {
+            return Err(line.error(line.text, "missing component name"));
+        }
+        vs.split_whitespace().for_each(|v| {
             add_item(u, v);
             add_item(v, u)
         });
-    });
-    map
+    }
+    if map.is_empty() {
+        return Err(ParseError::new(1, 1, "", "empty input"));
+    }
+    Ok(map)
 }
 
 fn find_source_part<'a>(
@@ -110,8 +119,8 @@ fn find_source_part<'a>(
     visited
 }
 
-pub fn part_one(input: &str) -> usize {
-    let graph = parse_input(input);
+pub fn try_part_one(input: &str) -> Result<usize, ParseError> {
+    let graph = parse_input(input)?;
     let source = graph.keys().next().unwrap();
     for sink in graph.keys().skip(1) {
         let mut graph = graph.clone();
@@ -120,14 +129,22 @@ pub fn part_one(input: &str) -> usize {
             let source_part = find_source_part(&graph, source);
             let a = source_part.len();
             let b = graph.len() - a;
-            return a * b;
+            return Ok(a * b);
         }
     }
-    0
+    Ok(0)
+}
+
+pub fn try_part_two<'a>(_: &str) -> Result<&'a str, ParseError> {
+    Ok("fifty stars")
+}
+
+pub fn part_one(input: &str) -> usize {
+    try_part_one(input).unwrap()
 }
 
-pub fn part_two<'a>(_: &str) -> &'a str {
-    "fifty stars"
+pub fn part_two<'a>(input: &str) -> &'a str {
+    try_part_two(input).unwrap()
 }
 
 #[cfg(test)]
@@ -0,0 +1,172 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum AocError {
+    UnknownDay(u8),
+    MissingFile {
+        path: PathBuf,
+    },
+    Unreadable {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Malformed {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    InvalidParam {
+        day: u8,
+        key: String,
+        reason: String,
+    },
+}
+
+impl AocError {
+    pub(crate) fn from_io(path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::NotFound => AocError::MissingFile { path },
+            _ => AocError::Unreadable { path, source },
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::UnknownDay(day) => {
+                write!(f, "day {} is not available", day)
+            }
+            AocError::MissingFile { path } => {
+                write!(f, "input file {} not found", path.display())
+            }
+            AocError::Unreadable { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            AocError::Malformed {
+                line,
+                column,
+                text,
+                reason,
+                ..
+            } => write!(
+                f,
+                "malformed input at line {}, column {}: {}: {:?}",
+                line, column, reason, text
+            ),
+            AocError::InvalidParam { day, key, reason } => write!(
+                f,
+                "invalid parameter {} for day {}: {}",
+                key, day, reason
+            ),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Unreadable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, reason: &str) -> Self {
+        ParseError {
+            line,
+            column,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn into_error(self, day: u8) -> AocError {
+        AocError::Malformed {
+            day,
+            line: self.line,
+            column: self.column,
+            text: self.text,
+            reason: self.reason,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}: {:?}",
+            self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::try_read_as_string;
+
+    #[test]
+    fn missing_file() {
+        let err = try_read_as_string(1, "no-such-file").unwrap_err();
+        assert!(matches!(err, AocError::MissingFile { .. }));
+        assert_eq!(
+            err.to_string(),
+            "input file inputs/01-no-such-file.txt not found"
+        );
+    }
+
+    #[test]
+    fn input_dir() {
+        let dir = PathBuf::from("/nonexistent/inputs");
+        assert_eq!(
+            crate::input_path(&dir, 7, "example"),
+            PathBuf::from("/nonexistent/inputs/07-example.txt")
+        );
+        let err = crate::try_read_from(&dir, 7, "example").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input file /nonexistent/inputs/07-example.txt not found"
+        );
+    }
+
+    #[test]
+    fn unreadable_file() {
+        let err = AocError::from_io(
+            PathBuf::from("inputs"),
+            io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+        assert!(matches!(err, AocError::Unreadable { .. }));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn malformed_line() {
+        let err = ParseError::new(3, 9, "Game 3: x", "expected ':'");
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 9: expected ':': \"Game 3: x\""
+        );
+        assert_eq!(
+            err.into_error(2).to_string(),
+            "malformed input at line 3, column 9: expected ':': \"Game 3: x\""
+        );
+    }
+}
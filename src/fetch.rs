@@ -0,0 +1,505 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// the session cookie of the site, overrides the config file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+// the site to download from, overrides the config file
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+// the config file, instead of ~/.config/aoc/config
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+
+// the site itself, which only speaks https
+pub const SITE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2023;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum FetchError {
+    NoSession,
+    // a cookie value cannot hold a line break
+    InvalidSession,
+    NoBaseUrl,
+    InvalidUrl(String),
+    Unsupported(String),
+    Config { path: PathBuf, line: usize },
+    Io(io::Error),
+    Status(u16, String),
+    Malformed(&'static str),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session token, set ${} or `session` in the config file",
+                SESSION_VAR
+            ),
+            FetchError::InvalidSession => {
+                write!(f, "the session token contains a line break")
+            }
+            FetchError::NoBaseUrl => write!(
+                f,
+                "no base url, {} needs https which is not supported, so \
+                 set --url, ${} or `base_url` in the config file to a \
+                 plain http endpoint such as a local proxy",
+                SITE_URL, BASE_URL_VAR
+            ),
+            FetchError::InvalidUrl(url) => {
+                write!(f, "invalid base url {:?}", url)
+            }
+            FetchError::Unsupported(url) => write!(
+                f,
+                "{:?} is not plain http, point the base url at an http \
+                 endpoint such as a local proxy",
+                url
+            ),
+            FetchError::Config { path, line } => write!(
+                f,
+                "{}:{}: expected `key = value`",
+                path.display(),
+                line
+            ),
+            FetchError::Io(e) => write!(f, "{}", e),
+            FetchError::Status(code, reason) => {
+                write!(f, "the server answered {} {}", code, reason)
+            }
+            FetchError::Malformed(reason) => {
+                write!(f, "malformed response: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::Io(e)
+    }
+}
+
+// the settings of the config file, `key = value` lines with `#` comments,
+// and of the environment, which wins
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn parse(text: &str, path: &Path) -> Result<Config, FetchError> {
+        let mut config = Config::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || FetchError::Config {
+                path: path.into(),
+                line: i + 1,
+            };
+            let (key, value) = line.split_once('=').ok_or_else(error)?;
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "session" => config.session = value,
+                "base_url" => config.base_url = value,
+                _ => return Err(error()),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        match env::var_os(CONFIG_VAR) {
+            Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+            _ => env::var_os("HOME")
+                .map(|home| Path::new(&home).join(".config/aoc/config")),
+        }
+    }
+
+    // a missing config file is an empty one
+    pub fn load() -> Result<Config, FetchError> {
+        let mut config = match Config::path() {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(text) => Config::parse(&text, &path)?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    Config::default()
+                }
+                Err(e) => return Err(e.into()),
+            },
+            None => Config::default(),
+        };
+        let var = |name| env::var(name).ok().filter(|v| !v.is_empty());
+        if let Some(session) = var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Some(base_url) = var(BASE_URL_VAR) {
+            config.base_url = Some(base_url);
+        }
+        Ok(config)
+    }
+}
+
+// a minimal HTTP/1.1 client, one connection per request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    host: String,
+    port: u16,
+    // the path of the base url, without a trailing slash
+    prefix: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Result<Client, FetchError> {
+        let invalid = || FetchError::InvalidUrl(base_url.to_string());
+        if session.contains(['\r', '\n']) {
+            return Err(FetchError::InvalidSession);
+        }
+        // it goes into the request line and the Host header as well
+        if base_url.contains(|c: char| c.is_whitespace() || c.is_control()) {
+            return Err(invalid());
+        }
+        let Some(rest) = base_url.strip_prefix("http://") else {
+            return Err(match base_url.contains("://") {
+                true => FetchError::Unsupported(base_url.to_string()),
+                false => invalid(),
+            });
+        };
+        let (authority, prefix) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                (host, port.parse().map_err(|_| invalid())?)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Client {
+            host: host.to_string(),
+            port,
+            prefix: prefix.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Client, FetchError> {
+        let session = config.session.as_ref().ok_or(FetchError::NoSession)?;
+        let base_url =
+            config.base_url.as_deref().ok_or(FetchError::NoBaseUrl)?;
+        Client::new(base_url, session)
+    }
+
+    pub fn input(&self, day: u8) -> Result<String, FetchError> {
+        self.get(&format!("/{}/day/{}/input", YEAR, day))
+    }
+
+    // the body of a 200 response
+    pub fn get(&self, path: &str) -> Result<String, FetchError> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| FetchError::InvalidUrl(self.host.clone()))?;
+        let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let host = match self.port {
+            80 => self.host.clone(),
+            port => format!("{}:{}", self.host, port),
+        };
+        write!(
+            stream,
+            "GET {}{} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Cookie: session={}\r\n\
+             User-Agent: aoc-2023-in-rust\r\n\
+             Connection: close\r\n\
+             \r\n",
+            self.prefix, path, host, self.session
+        )?;
+        let mut response = vec![];
+        stream.read_to_end(&mut response)?;
+        parse_response(&response)
+    }
+}
+
+fn parse_response(response: &[u8]) -> Result<String, FetchError> {
+    let Some(end) = response.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return Err(FetchError::Malformed("no end of the headers"));
+    };
+    let head = std::str::from_utf8(&response[..end])
+        .map_err(|_| FetchError::Malformed("headers are not utf-8"))?;
+    let body = &response[end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines.next().unwrap_or_default();
+    let mut fields = status.splitn(3, ' ');
+    let (Some(version), Some(code)) = (fields.next(), fields.next()) else {
+        return Err(FetchError::Malformed("no status line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(FetchError::Malformed("not an HTTP/1.x response"));
+    }
+    let code: u16 = code
+        .parse()
+        .map_err(|_| FetchError::Malformed("invalid status code"))?;
+    if code != 200 {
+        let reason = fields.next().unwrap_or_default();
+        return Err(FetchError::Status(code, reason.to_string()));
+    }
+
+    let mut chunked = false;
+    let mut length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(FetchError::Malformed("invalid header"));
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "transfer-encoding" => {
+                chunked = value.eq_ignore_ascii_case("chunked")
+            }
+            "content-length" => {
+                length = Some(value.parse::<usize>().map_err(|_| {
+                    FetchError::Malformed("invalid content length")
+                })?)
+            }
+            _ => {}
+        }
+    }
+
+    let body = match (chunked, length) {
+        (true, _) => dechunk(body)?,
+        (false, Some(n)) if n <= body.len() => body[..n].to_vec(),
+        (false, Some(_)) => return Err(FetchError::Malformed("short body")),
+        (false, None) => body.to_vec(),
+    };
+    String::from_utf8(body).map_err(|_| FetchError::Malformed("not utf-8"))
+}
+
+// a chunked body is hex sized chunks up to one of size 0
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, FetchError> {
+    let malformed = || FetchError::Malformed("invalid chunk");
+    let mut data = vec![];
+    loop {
+        let end = body.windows(2).position(|w| w == b"\r\n");
+        let end = end.ok_or_else(malformed)?;
+        let size =
+            std::str::from_utf8(&body[..end]).map_err(|_| malformed())?;
+        // chunk extensions follow a ';'
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size =
+            usize::from_str_radix(size, 16).map_err(|_| malformed())?;
+        body = &body[end + 2..];
+        if size == 0 {
+            return Ok(data);
+        }
+        if body.len() < size + 2 || &body[size..size + 2] != b"\r\n" {
+            return Err(malformed());
+        }
+        data.extend_from_slice(&body[..size]);
+        body = &body[size + 2..];
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf, usize),
+}
+
+// Downloads the input of `day` into `dir` where the solvers read it, an
+// input that is already there is never downloaded again. The client is
+// only made when a download is needed.
+pub fn fetch_input(
+    dir: &Path,
+    day: u8,
+    client: impl FnOnce() -> Result<Client, FetchError>,
+) -> Result<Fetched, FetchError> {
+    let path = crate::input_path(dir, day, "input");
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client()?.input(day)?;
+    // it would be cached and never downloaded again
+    if input.is_empty() {
+        return Err(FetchError::Malformed("empty input"));
+    }
+    fs::create_dir_all(dir)?;
+    // a partial file would look cached the next time
+    let partial = path.with_extension("part");
+    fs::write(&partial, &input)?;
+    fs::rename(&partial, &path)?;
+    Ok(Fetched::Downloaded(path, input.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+    use std::thread;
+
+    // answers each request with the next response, returns the requests
+    fn serve(
+        responses: Vec<&'static str>,
+    ) -> (u16, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = io::BufReader::new(&stream);
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    request.push_str(&line);
+                }
+                (&stream).write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn download() {
+        let (port, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1 2 3\n",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+             4\r\nabc\n\r\n3;x=y\r\ndef\r\n0\r\n\r\n",
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+        ]);
+        let base = format!("http://127.0.0.1:{}/aoc/", port);
+        let client = || Client::new(&base, "secret");
+        let dir =
+            env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let path = crate::input_path(&dir, 5, "input");
+        assert_eq!(
+            fetch_input(&dir, 5, client).unwrap(),
+            Fetched::Downloaded(path.clone(), 6)
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2 3\n");
+        // the server is not asked again
+        let unused = || -> Result<Client, FetchError> { panic!() };
+        assert_eq!(
+            fetch_input(&dir, 5, unused).unwrap(),
+            Fetched::Cached(path)
+        );
+
+        assert_eq!(client().unwrap().input(6).unwrap(), "abc\ndef");
+        assert!(matches!(
+            fetch_input(&dir, 7, client),
+            Err(FetchError::Status(404, _))
+        ));
+        assert!(!crate::input_path(&dir, 7, "input").exists());
+        assert!(matches!(
+            fetch_input(&dir, 8, client),
+            Err(FetchError::Malformed("empty input"))
+        ));
+        assert!(!crate::input_path(&dir, 8, "input").exists());
+
+        let requests = server.join().unwrap();
+        assert!(
+            requests[0].starts_with("GET /aoc/2023/day/5/input HTTP/1.1\r\n")
+        );
+        assert!(requests[0].contains("Cookie: session=secret\r\n"));
+        assert!(
+            requests[0].contains(&format!("Host: 127.0.0.1:{}\r\n", port))
+        );
+        assert!(requests[1].starts_with("GET /aoc/2023/day/6/input "));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn urls() {
+        let client = Client::new("http://localhost", "s").unwrap();
+        assert_eq!((client.host.as_str(), client.port), ("localhost", 80));
+        assert_eq!(client.prefix, "");
+        assert!(matches!(
+            Client::new(SITE_URL, "s"),
+            Err(FetchError::Unsupported(_))
+        ));
+        assert!(matches!(
+            Client::new("http://localhost", "s\r\nX-Evil: 1"),
+            Err(FetchError::InvalidSession)
+        ));
+        let config = Config {
+            session: Some("s".to_string()),
+            ..Config::default()
+        };
+        assert!(matches!(
+            Client::from_config(&config),
+            Err(FetchError::NoBaseUrl)
+        ));
+        assert!(matches!(
+            Client::new("http://localhost/\r\nX-Evil: 1", "s"),
+            Err(FetchError::InvalidUrl(_))
+        ));
+        assert!(Client::new("localhost:80", "s").is_err());
+        assert!(Client::new("http://:80", "s").is_err());
+        assert!(Client::new("http://host:port", "s").is_err());
+        assert!(matches!(
+            Client::from_config(&Config::default()),
+            Err(FetchError::NoSession)
+        ));
+    }
+
+    #[test]
+    fn config() {
+        let path = Path::new("config");
+        let text =
+            "# aoc\nsession = abc\n\nbase_url = http://localhost:8080\n";
+        let config = Config::parse(text, path).unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080"));
+        let err = Config::parse("session abc", path).unwrap_err();
+        assert_eq!(err.to_string(), "config:1: expected `key = value`");
+        assert!(Config::parse("token = abc", path).is_err());
+    }
+
+    #[test]
+    fn responses() {
+        assert!(matches!(
+            parse_response(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\nabc"
+            ),
+            Err(FetchError::Malformed("short body"))
+        ));
+        assert!(parse_response(b"garbage").is_err());
+        assert!(parse_response(b"SMTP 200 OK\r\n\r\n").is_err());
+        assert_eq!(
+            parse_response(b"HTTP/1.0 200 OK\r\n\r\nabc").unwrap(),
+            "abc"
+        );
+    }
+}
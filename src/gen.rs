@@ -0,0 +1,1057 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use crate::geom::{Dir, Point2};
+
+// SplitMix64, small and good enough to make inputs with
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // 0..n, n must not be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (lo, hi) = range.into_inner();
+        let n = (hi - lo) as u128 + 1;
+        lo + ((self.next_u64() as u128 * n) >> 64) as i64
+    }
+
+    pub fn range_usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (lo, hi) = range.into_inner();
+        lo + self.below(hi - lo + 1)
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+pub type GenerateFn = fn(&mut Rng, usize) -> String;
+
+pub struct Generator {
+    // what the size is the number of
+    pub size: &'static str,
+    pub sizes: RangeInclusive<usize>,
+    pub default_size: usize,
+    generate: GenerateFn,
+}
+
+impl Generator {
+    // a random input of the day, None if the size is out of range
+    pub fn generate(&self, size: usize, seed: u64) -> Option<String> {
+        if !self.sizes.contains(&size) {
+            return None;
+        }
+        Some((self.generate)(&mut Rng::new(seed), size))
+    }
+}
+
+const fn gen(
+    size: &'static str,
+    sizes: RangeInclusive<usize>,
+    default_size: usize,
+    generate: GenerateFn,
+) -> Generator {
+    Generator {
+        size,
+        sizes,
+        default_size,
+        generate,
+    }
+}
+
+pub fn generator(day: u8) -> Option<Generator> {
+    let generator = match day {
+        1 => gen("lines", 1..=100_000, 1000, day01),
+        2 => gen("games", 1..=100_000, 100, day02),
+        3 => gen("rows and columns", 1..=1000, 140, day03),
+        4 => gen("cards", 1..=100_000, 200, day04),
+        5 => gen("ranges per map", 1..=1000, 30, day05),
+        // the kerned race of part two must fit in a u64
+        6 => gen("races", 1..=4, 4, day06),
+        7 => gen("hands", 1..=100_000, 1000, day07),
+        // five distinct primes up to the size
+        8 => gen("steps of the longest ghost", 11..=1000, 300, day08),
+        9 => gen("histories", 1..=100_000, 200, day09),
+        10 => gen("rows and columns", 2..=1000, 140, day10),
+        11 => gen("rows and columns", 1..=1000, 140, day11),
+        // the counts of part two must fit in a usize
+        12 => gen("springs in a row", 1..=20, 20, day12),
+        13 => gen("patterns", 1..=10_000, 100, day13),
+        14 => gen("rows and columns", 1..=200, 100, day14),
+        15 => gen("steps", 1..=100_000, 4000, day15),
+        16 => gen("rows and columns", 1..=200, 110, day16),
+        17 => gen("rows and columns", 5..=300, 141, day17),
+        18 => gen("corners per side", 2..=300, 100, day18),
+        19 => gen("workflows", 1..=5000, 500, day19),
+        // four counters, so the answer of part two fits in a u64
+        20 => gen("bits per counter", 1..=16, 12, day20),
+        21 => gen("rows and columns", 3..=301, 131, day21),
+        22 => gen("bricks", 1..=5000, 1200, day22),
+        23 => gen("junctions per side", 1..=6, 6, day23),
+        24 => gen("hailstones", 5..=1000, 300, day24),
+        25 => gen("components per side", 5..=2000, 700, day25),
+        _ => return None,
+    };
+    Some(generator)
+}
+
+fn digit(rng: &mut Rng) -> char {
+    char::from(b'0' + rng.range(1..=9) as u8)
+}
+
+fn letter(rng: &mut Rng) -> char {
+    char::from(b'a' + rng.below(26) as u8)
+}
+
+// a name of `len` lowercase letters that is not used yet
+fn name(rng: &mut Rng, used: &mut HashSet<String>, len: usize) -> String {
+    loop {
+        let name: String = (0..len).map(|_| letter(rng)).collect();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+fn grid(
+    rng: &mut Rng,
+    size: usize,
+    mut tile: impl FnMut(&mut Rng) -> char,
+) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        let row: String = (0..size).map(|_| tile(rng)).collect();
+        writeln!(s, "{}", row).unwrap();
+    }
+    s
+}
+
+// The cells of a loop in a w x h box, from the bottom left corner up the
+// left side, along a random skyline to the right side and back along a
+// random floor. The top and the floor never meet, so the loop is simple.
+fn skyline(rng: &mut Rng, w: usize, h: usize) -> Vec<Point2<i32>> {
+    let mid = h as i64 / 2;
+    let mut top: Vec<i64> = (0..w).map(|_| rng.range(0..=mid - 1)).collect();
+    let mut floor: Vec<i64> =
+        (0..w).map(|_| rng.range(mid..=h as i64 - 1)).collect();
+    // the sides go straight up and down
+    top[w - 1] = top[w - 2];
+    floor[0] = floor[1];
+
+    let mut cells = vec![];
+    let mut pos = Point2::new(0, floor[0] as i32);
+    // over to column x, then up or down to row y
+    let mut walk = |pos: &mut Point2<i32>, x: usize, y: i64| {
+        for to in [
+            Point2::new(x as i32, pos.y),
+            Point2::new(x as i32, y as i32),
+        ] {
+            while *pos != to {
+                cells.push(*pos);
+                pos.x += (to.x - pos.x).signum();
+                pos.y += (to.y - pos.y).signum();
+            }
+        }
+    };
+    for (x, &y) in top.iter().enumerate() {
+        walk(&mut pos, x, y);
+    }
+    for (x, &y) in floor.iter().enumerate().rev() {
+        walk(&mut pos, x, y);
+    }
+    cells
+}
+
+fn towards(from: Point2<i32>, to: Point2<i32>) -> Dir {
+    Dir::ALL.into_iter().find(|&d| from.step(d) == to).unwrap()
+}
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+// letters, digits and spelled digits with at least one digit
+fn day01(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        for _ in 0..rng.range_usize(1..=8) {
+            match rng.below(4) {
+                0 => line.push(digit(rng)),
+                1 => line.push_str(rng.pick::<&str>(&WORDS)),
+                _ => line.push(letter(rng)),
+            }
+        }
+        if !line.contains(|c: char| c.is_ascii_digit()) {
+            let i = rng.below(line.len() + 1);
+            line.insert(i, digit(rng));
+        }
+        writeln!(s, "{}", line).unwrap();
+    }
+    s
+}
+
+fn day02(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for game in 1..=size {
+        let mut draws = vec![];
+        for _ in 0..rng.range_usize(1..=6) {
+            let mut colors = ["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            let mut cubes = vec![];
+            for color in &colors[..rng.range_usize(1..=3)] {
+                cubes.push(format!("{} {}", rng.range(1..=20), color));
+            }
+            draws.push(cubes.join(", "));
+        }
+        writeln!(s, "Game {}: {}", game, draws.join("; ")).unwrap();
+    }
+    s
+}
+
+// numbers are never next to each other in a row
+fn day03(rng: &mut Rng, size: usize) -> String {
+    const SYMBOLS: &[u8] = b"*#+$/@%=&-";
+    let mut s = String::new();
+    for _ in 0..size {
+        let mut row = vec![b'.'; size];
+        let mut x = 0;
+        while x < size {
+            if rng.chance(0.15) {
+                let len = rng.range_usize(1..=3).min(size - x);
+                row[x] = digit(rng) as u8;
+                for c in row[x + 1..x + len].iter_mut() {
+                    *c = b'0' + rng.below(10) as u8;
+                }
+                x += len + 1;
+            } else {
+                if rng.chance(0.08) {
+                    row[x] = *rng.pick(SYMBOLS);
+                }
+                x += 1;
+            }
+        }
+        writeln!(s, "{}", String::from_utf8(row).unwrap()).unwrap();
+    }
+    s
+}
+
+// half of the cards win nothing and the copies stay far from overflowing
+fn day04(rng: &mut Rng, size: usize) -> String {
+    let mut copies = vec![1u64; size];
+    let mut s = String::new();
+    for i in 0..size {
+        let mut matches = match rng.chance(0.5) {
+            true => 0,
+            false => rng.range_usize(1..=10),
+        };
+        if copies[i] > 1 << 30 {
+            matches = 0;
+        }
+        matches = matches.min(size - i - 1);
+        for j in i + 1..=i + matches {
+            copies[j] += copies[i];
+        }
+
+        let mut numbers: Vec<u32> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let winning = &numbers[..10];
+        let mut have = winning[..matches].to_vec();
+        have.extend(&numbers[10..35 - matches]);
+        rng.shuffle(&mut have);
+        let list = |v: &[u32]| {
+            let v: Vec<String> =
+                v.iter().map(|n| format!("{:2}", n)).collect();
+            v.join(" ")
+        };
+        let card = format!("Card {:3}:", i + 1);
+        writeln!(s, "{} {} | {}", card, list(winning), list(&have)).unwrap();
+    }
+    s
+}
+
+// the sources of a map never overlap and neither do the destinations
+fn day05(rng: &mut Rng, size: usize) -> String {
+    const MAPS: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+    const SPACE: i64 = 1 << 32;
+    let mut seeds = vec![];
+    for _ in 0..5 {
+        let start = rng.range(0..=SPACE - 2);
+        let len = rng.range(1..=(SPACE - start - 1).min(1 << 28));
+        seeds.push(format!("{} {}", start, len));
+    }
+    let mut s = format!("seeds: {}\n", seeds.join(" "));
+
+    for map in MAPS {
+        writeln!(s, "\n{} map:", map).unwrap();
+        let mut cuts: Vec<i64> =
+            (1..2 * size).map(|_| rng.range(1..=SPACE - 1)).collect();
+        cuts.extend([0, SPACE]);
+        cuts.sort();
+        cuts.dedup();
+        let mut pieces: Vec<(i64, i64)> =
+            cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        rng.shuffle(&mut pieces);
+        pieces.truncate(size);
+        let total: i64 = pieces.iter().map(|p| p.1).sum();
+        let mut dest = rng.range(0..=SPACE - total);
+        for (source, len) in pieces {
+            writeln!(s, "{} {} {}", dest, source, len).unwrap();
+            dest += len;
+        }
+    }
+    s
+}
+
+// two digit times, so the race of part two is their concatenation
+fn day06(rng: &mut Rng, size: usize) -> String {
+    let wins =
+        |time: u64, distance: u64| (time / 2) * (time - time / 2) > distance;
+    loop {
+        let races: Vec<(u64, u64)> = (0..size)
+            .map(|_| {
+                let time = rng.range(10..=99) as u64;
+                let best = (time / 2) * (time - time / 2);
+                (time, rng.range(best as i64 / 2..=best as i64 - 1) as u64)
+            })
+            .collect();
+        let kerned = |f: fn(&(u64, u64)) -> u64| {
+            races.iter().map(|r| f(r).to_string()).collect::<String>()
+        };
+        let time: u64 = kerned(|r| r.0).parse().unwrap();
+        let distance: u64 = kerned(|r| r.1).parse().unwrap();
+        if !wins(time, distance) {
+            continue;
+        }
+        let (mut times, mut distances) =
+            ("Time:    ".to_string(), "Distance:".to_string());
+        for (time, distance) in races {
+            write!(times, " {:6}", time).unwrap();
+            write!(distances, " {:6}", distance).unwrap();
+        }
+        return format!("{}\n{}\n", times, distances);
+    }
+}
+
+// distinct hands of every type
+fn day07(rng: &mut Rng, size: usize) -> String {
+    const CARDS: &[u8] = b"AKQJT98765432";
+    let mut seen = HashSet::new();
+    let mut s = String::new();
+    while seen.len() < size {
+        let mut cards = CARDS.to_vec();
+        rng.shuffle(&mut cards);
+        cards.truncate(rng.range_usize(1..=5));
+        let hand: String =
+            (0..5).map(|_| char::from(*rng.pick(&cards))).collect();
+        if seen.insert(hand.clone()) {
+            writeln!(s, "{} {}", hand, rng.range(1..=1000)).unwrap();
+        }
+    }
+    s
+}
+
+// Every ghost walks two lanes side by side, whatever the instructions it
+// moves one step along them. After a lead-in of its own length it
+// reaches its Z node every `length` steps, where the lengths are
+// distinct primes, so the first time on Z is not the period. Some loops
+// have a second Z node on one lane only, which the ghost is on or not
+// depending on the instruction, and no loop is as long as the
+// instructions.
+fn day08(rng: &mut Rng, size: usize) -> String {
+    let mut primes: Vec<usize> = (2..=size)
+        .filter(|&n| (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+        .collect();
+    rng.shuffle(&mut primes);
+    let mut used = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut node = |rng: &mut Rng, last: Option<char>| loop {
+        let mut name: String =
+            (0..3).map(|_| letter(rng).to_ascii_uppercase()).collect();
+        match last {
+            Some(c) => name.replace_range(2.., &c.to_string()),
+            None if name.ends_with(['A', 'Z']) => continue,
+            None => (),
+        }
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let mut nodes = vec![];
+    for (ghost, &length) in primes[..5].iter().enumerate() {
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (node(rng, Some('A')), node(rng, Some('Z'))),
+        };
+        let lead = rng.range_usize(1..=size / 2);
+        let second = rng
+            .chance(0.5)
+            .then(|| rng.range_usize(lead..=lead + length - 2));
+        let lanes: Vec<(String, String)> = (1..lead + length)
+            .map(|i| {
+                let mut pair = match second == Some(i - 1) {
+                    true => [node(rng, Some('Z')), node(rng, None)],
+                    false => [node(rng, None), node(rng, None)],
+                };
+                rng.shuffle(&mut pair);
+                let [a, b] = pair;
+                (a, b)
+            })
+            .collect();
+        nodes.push((start, lanes[0].clone()));
+        nodes.push((end.clone(), lanes[lead].clone()));
+        for (i, (a, b)) in lanes.iter().enumerate() {
+            let next = match lanes.get(i + 1) {
+                Some(lane) => lane.clone(),
+                None => (end.clone(), end.clone()),
+            };
+            nodes.push((a.clone(), next.clone()));
+            nodes.push((b.clone(), next));
+        }
+    }
+    rng.shuffle(&mut nodes);
+
+    let len = loop {
+        let len = rng.range_usize(2..=size);
+        if !primes[..5].contains(&len) {
+            break len;
+        }
+    };
+    let mut s: String = (0..len)
+        .map(|_| if rng.chance(0.5) { 'L' } else { 'R' })
+        .collect();
+    s.push_str("\n\n");
+    for (name, (left, right)) in nodes {
+        writeln!(s, "{} = ({}, {})", name, left, right).unwrap();
+    }
+    s
+}
+
+// polynomials of up to the sixth degree
+fn day09(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..size {
+        let coefficients: Vec<i64> =
+            (0..=rng.range(0..=6)).map(|_| rng.range(-5..=5)).collect();
+        let values: Vec<String> = (-5..16)
+            .map(|x| coefficients.iter().fold(0, |v, c| v * x + c))
+            .map(|v| v.to_string())
+            .collect();
+        writeln!(s, "{}", values.join(" ")).unwrap();
+    }
+    s
+}
+
+// a skyline loop among random pipes, only ground around the start
+fn day10(rng: &mut Rng, size: usize) -> String {
+    const JUNK: &[u8] = b"|-LJ7F...";
+    let cells = skyline(rng, size, size);
+    let mut grid: Vec<Vec<u8>> = (0..size + 2)
+        .map(|_| (0..size + 2).map(|_| *rng.pick(JUNK)).collect())
+        .collect();
+    let n = cells.len();
+    for (i, &cell) in cells.iter().enumerate() {
+        let prev = towards(cell, cells[(i + n - 1) % n]);
+        let next = towards(cell, cells[(i + 1) % n]);
+        let pipe = match Dir::ALL.map(|d| d == prev || d == next) {
+            [_, true, _, true] => b'|',
+            [true, _, true, _] => b'-',
+            [true, _, _, true] => b'L',
+            [_, _, true, true] => b'J',
+            [_, true, true, _] => b'7',
+            _ => b'F',
+        };
+        grid[cell.y as usize + 1][cell.x as usize + 1] = pipe;
+    }
+
+    let start = *rng.pick(&cells) + Point2::new(1, 1);
+    for pos in start.neighbours4() {
+        if !cells.contains(&(pos - Point2::new(1, 1))) {
+            grid[pos.y as usize][pos.x as usize] = b'.';
+        }
+    }
+    grid[start.y as usize][start.x as usize] = b'S';
+    grid.into_iter()
+        .map(|row| String::from_utf8(row).unwrap() + "\n")
+        .collect()
+}
+
+// a tenth of the rows and columns are empty
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let empty_rows: Vec<bool> = (0..size).map(|_| rng.chance(0.1)).collect();
+    let empty_cols: Vec<bool> = (0..size).map(|_| rng.chance(0.1)).collect();
+    let mut s = String::new();
+    for &empty_row in &empty_rows {
+        for &empty_col in &empty_cols {
+            let galaxy = !empty_row && !empty_col && rng.chance(0.05);
+            s.push(if galaxy { '#' } else { '.' });
+        }
+        s.push('\n');
+    }
+    s
+}
+
+// 100 rows of up to `size` springs, a random row with some hidden
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..100 {
+        let len = rng.range_usize(1..=size);
+        let mut row: Vec<u8> = (0..len)
+            .map(|_| if rng.chance(0.5) { b'#' } else { b'.' })
+            .collect();
+        if !row.contains(&b'#') {
+            row[rng.below(len)] = b'#';
+        }
+        let groups: Vec<String> = row
+            .split(|&c| c == b'.')
+            .filter(|g| !g.is_empty())
+            .map(|g| g.len().to_string())
+            .collect();
+        for c in row.iter_mut() {
+            if rng.chance(0.4) {
+                *c = b'?';
+            }
+        }
+        let row = String::from_utf8(row).unwrap();
+        writeln!(s, "{} {}", row, groups.join(",")).unwrap();
+    }
+    s
+}
+
+fn transpose(pattern: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    (0..pattern[0].len())
+        .map(|x| pattern.iter().map(|row| row[x]).collect())
+        .collect()
+}
+
+// the differences across every horizontal line of reflection
+fn smudges(pattern: &[Vec<bool>]) -> Vec<usize> {
+    let h = pattern.len();
+    (1..h)
+        .map(|a| {
+            (0..a.min(h - a))
+                .map(|i| {
+                    let (r1, r2) = (&pattern[a - 1 - i], &pattern[a + i]);
+                    r1.iter().zip(r2).filter(|(a, b)| a != b).count()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+// Mirrored across a row and across a column, then one cell is flipped
+// that breaks the column but not the row, so that the row is the line of
+// part one and the column the line of part two.
+fn pattern(rng: &mut Rng) -> Vec<Vec<bool>> {
+    loop {
+        let (h, w) = (rng.range_usize(5..=17), rng.range_usize(5..=17));
+        let mut pattern: Vec<Vec<bool>> = (0..h)
+            .map(|_| (0..w).map(|_| rng.chance(0.5)).collect())
+            .collect();
+        let a = rng.range_usize(1..=h - 1);
+        let k = a.min(h - a);
+        if 2 * k == h {
+            continue;
+        }
+        for i in 0..k {
+            pattern[a + i] = pattern[a - 1 - i].clone();
+        }
+        let b = rng.range_usize(1..=w - 1);
+        for row in pattern.iter_mut() {
+            for i in 0..b.min(w - b) {
+                row[b + i] = row[b - 1 - i];
+            }
+        }
+        let r = match a < h - a {
+            true => rng.range_usize(2 * a..=h - 1),
+            false => rng.range_usize(0..=h - 2 * k - 1),
+        };
+        let c = rng.range_usize(b - b.min(w - b)..=b + b.min(w - b) - 1);
+        pattern[r][c] = !pattern[r][c];
+
+        if rng.chance(0.5) {
+            pattern = transpose(&pattern);
+        }
+        let mut lines = smudges(&pattern);
+        lines.extend(smudges(&transpose(&pattern)));
+        let count = |n| lines.iter().filter(|&&d| d == n).count();
+        if count(0) == 1 && count(1) == 1 {
+            return pattern;
+        }
+    }
+}
+
+fn day13(rng: &mut Rng, size: usize) -> String {
+    let patterns: Vec<String> = (0..size)
+        .map(|_| {
+            pattern(rng)
+                .iter()
+                .map(|row| {
+                    let row: String = row
+                        .iter()
+                        .map(|&rock| if rock { '#' } else { '.' })
+                        .collect();
+                    row + "\n"
+                })
+                .collect()
+        })
+        .collect();
+    patterns.join("\n")
+}
+
+fn day14(rng: &mut Rng, size: usize) -> String {
+    grid(rng, size, |rng| match rng.below(10) {
+        0 | 1 => 'O',
+        2 => '#',
+        _ => '.',
+    })
+}
+
+// labels from a small pool so that lenses get replaced and removed
+fn day15(rng: &mut Rng, size: usize) -> String {
+    let mut used = HashSet::new();
+    let labels: Vec<String> = (0..size / 4 + 1)
+        .map(|_| {
+            let len = rng.range_usize(2..=6);
+            name(rng, &mut used, len)
+        })
+        .collect();
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = rng.pick(&labels);
+            match rng.chance(0.7) {
+                true => format!("{}={}", label, digit(rng)),
+                false => format!("{}-", label),
+            }
+        })
+        .collect();
+    steps.join(",") + "\n"
+}
+
+fn day16(rng: &mut Rng, size: usize) -> String {
+    grid(rng, size, |rng| match rng.chance(0.1) {
+        true => *rng.pick(&['/', '\\', '|', '-']),
+        false => '.',
+    })
+}
+
+fn day17(rng: &mut Rng, size: usize) -> String {
+    grid(rng, size, digit)
+}
+
+// A skyline polygon. Part one stretches its columns and rows by up to
+// 10, part two by as much as the five hex digits allow.
+fn day18(rng: &mut Rng, size: usize) -> String {
+    let cells = skyline(rng, size, size);
+    let mut corners: Vec<Point2<i32>> = (0..cells.len())
+        .filter(|&i| {
+            let prev = cells[(i + cells.len() - 1) % cells.len()];
+            let next = cells[(i + 1) % cells.len()];
+            towards(prev, cells[i]) != towards(cells[i], next)
+        })
+        .map(|i| cells[i])
+        .collect();
+    corners.push(corners[0]);
+
+    let mut stretch = |most: i64| -> Vec<i64> {
+        let mut at = vec![0];
+        for _ in 0..size {
+            at.push(at.last().unwrap() + rng.range(1..=most));
+        }
+        at
+    };
+    let (x1, y1) = (stretch(10), stretch(10));
+    let most = 0xfffff / size as i64;
+    let (x2, y2) = (stretch(most), stretch(most));
+
+    let mut s = String::new();
+    for w in corners.windows(2) {
+        let (p, q) = (w[0], w[1]);
+        let step = Point2::new((q.x - p.x).signum(), (q.y - p.y).signum());
+        let dir = towards(p, p + step);
+        let len = |xs: &[i64], ys: &[i64]| {
+            (xs[q.x as usize] - xs[p.x as usize]).abs()
+                + (ys[q.y as usize] - ys[p.y as usize]).abs()
+        };
+        let (letter, hex) = match dir {
+            Dir::E => ('R', 0),
+            Dir::S => ('D', 1),
+            Dir::W => ('L', 2),
+            Dir::N => ('U', 3),
+        };
+        let (len1, len2) = (len(&x1, &y1), len(&x2, &y2));
+        writeln!(s, "{} {} (#{:05x}{})", letter, len1, len2, hex).unwrap();
+    }
+    s
+}
+
+// A tree of workflows below `in` and 200 parts. Every rule splits the
+// ratings that reach it in two, none of its conditions always holds.
+fn day19(rng: &mut Rng, size: usize) -> String {
+    const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+    let mut used = HashSet::from(["in".to_string()]);
+    let mut queue = vec![("in".to_string(), [(1, 4000); 4])];
+    let mut left = size - 1;
+    let mut workflows = vec![];
+    while let Some((workflow, mut ranges)) = queue.pop() {
+        // the last target of the last workflow continues the tree
+        let mut target = |rng: &mut Rng, ranges, fallback: bool| {
+            let last = fallback && queue.is_empty();
+            if left == 0 || !(last || rng.chance(0.4)) {
+                return rng.pick(&["A", "R"]).to_string();
+            }
+            left -= 1;
+            let len = rng.range_usize(2..=3);
+            let target = name(rng, &mut used, len);
+            queue.push((target.clone(), ranges));
+            target
+        };
+        let mut rules = vec![];
+        for _ in 0..rng.range_usize(1..=3) {
+            let split: Vec<usize> =
+                (0..4).filter(|&c| ranges[c].0 < ranges[c].1).collect();
+            if split.is_empty() {
+                break;
+            }
+            let c = *rng.pick(&split);
+            let (lo, hi) = ranges[c];
+            let mut matching = ranges;
+            let (op, value) = match rng.chance(0.5) {
+                true => {
+                    let value = rng.range(lo + 1..=hi);
+                    (matching[c], ranges[c]) = ((lo, value - 1), (value, hi));
+                    ('<', value)
+                }
+                false => {
+                    let value = rng.range(lo..=hi - 1);
+                    (matching[c], ranges[c]) = ((value + 1, hi), (lo, value));
+                    ('>', value)
+                }
+            };
+            let target = target(rng, matching, false);
+            rules
+                .push(format!("{}{}{}:{}", CATEGORIES[c], op, value, target));
+        }
+        rules.push(target(rng, ranges, true));
+        workflows.push(format!("{}{{{}}}", workflow, rules.join(",")));
+    }
+    rng.shuffle(&mut workflows);
+
+    let mut s = workflows.join("\n") + "\n\n";
+    for _ in 0..200 {
+        let p = [(); 4].map(|_| rng.range(1..=4000));
+        writeln!(s, "{{x={},m={},a={},s={}}}", p[0], p[1], p[2], p[3])
+            .unwrap();
+    }
+    s
+}
+
+// Four binary counters of flip-flops, each resets when its conjunction
+// sees its period. The conjunction before rx sees them all reset at the
+// least common multiple of the periods.
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let mut used = HashSet::from(["rx".to_string()]);
+    let last = name(rng, &mut used, 2);
+    let mut modules = vec![format!("&{} -> rx", last)];
+    let mut firsts = vec![];
+    for _ in 0..4 {
+        let period = rng.range(1 << (size - 1)..=(1 << size) - 1) | 1;
+        let flips: Vec<String> =
+            (0..size).map(|_| name(rng, &mut used, 2)).collect();
+        let conjunction = name(rng, &mut used, 2);
+        let inverter = name(rng, &mut used, 2);
+        let mut resets = vec![flips[0].clone(), inverter.clone()];
+        for (i, flip) in flips.iter().enumerate() {
+            let mut outputs: Vec<String> =
+                flips.get(i + 1).cloned().into_iter().collect();
+            match (period >> i) & 1 {
+                1 => outputs.push(conjunction.clone()),
+                _ => resets.push(flip.clone()),
+            }
+            rng.shuffle(&mut outputs);
+            modules.push(format!("%{} -> {}", flip, outputs.join(", ")));
+        }
+        rng.shuffle(&mut resets);
+        modules.push(format!("&{} -> {}", conjunction, resets.join(", ")));
+        modules.push(format!("&{} -> {}", inverter, last));
+        firsts.push(flips[0].clone());
+    }
+    modules.push(format!("broadcaster -> {}", firsts.join(", ")));
+    rng.shuffle(&mut modules);
+    modules.join("\n") + "\n"
+}
+
+// an odd square with the start in the middle of a clear row, column and
+// border
+fn day21(rng: &mut Rng, size: usize) -> String {
+    let size = size | 1;
+    let mid = size / 2;
+    let clear = |i| i == 0 || i == mid || i == size - 1;
+    let mut s = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            s.push(match (x, y) {
+                _ if x == mid && y == mid => 'S',
+                _ if clear(x) || clear(y) => '.',
+                _ if rng.chance(0.15) => '#',
+                _ => '.',
+            });
+        }
+        s.push('\n');
+    }
+    s
+}
+
+// bricks of up to four cubes above a 10 x 10 floor, none overlapping
+fn day22(rng: &mut Rng, size: usize) -> String {
+    let height = size as i64 / 5 + 10;
+    let mut filled = HashSet::new();
+    let mut bricks = vec![];
+    while bricks.len() < size {
+        let len = rng.range(0..=3);
+        let mut end = [0; 3];
+        end[rng.below(3)] = len;
+        let start = [
+            rng.range(0..=9 - end[0]),
+            rng.range(0..=9 - end[1]),
+            rng.range(1..=height),
+        ];
+        let end = [0, 1, 2].map(|i| start[i] + end[i]);
+        let cubes: Vec<[i64; 3]> = (start[0]..=end[0])
+            .flat_map(|x| (start[1]..=end[1]).map(move |y| (x, y)))
+            .flat_map(|(x, y)| (start[2]..=end[2]).map(move |z| [x, y, z]))
+            .collect();
+        if cubes.iter().any(|c| filled.contains(c)) {
+            continue;
+        }
+        filled.extend(cubes);
+        bricks.push(format!(
+            "{},{},{}~{},{},{}",
+            start[0], start[1], start[2], end[0], end[1], end[2]
+        ));
+    }
+    bricks.join("\n") + "\n"
+}
+
+// A lattice of junctions joined by trails that have a slope at both ends
+// pointing away from the start. Past the first row and column the trails
+// make a detour, rightwards ones up into the left half of the block above
+// and downwards ones left into the top half of the block to their left,
+// so that the paths differ in length and the detours never meet.
+fn day23(rng: &mut Rng, size: usize) -> String {
+    let (top, bottom) = (rng.range_usize(2..=6), rng.range_usize(2..=6));
+    let mut spacing = |first: usize| {
+        let mut at = vec![first];
+        for _ in 1..size {
+            at.push(at.last().unwrap() + rng.range_usize(8..=14));
+        }
+        at
+    };
+    let xs = spacing(1);
+    let ys = spacing(top);
+    let (w, h) = (xs[size - 1] + 2, ys[size - 1] + bottom);
+    let mut grid = vec![vec![b'#'; w]; h];
+    let mut carve = |corners: &[(usize, usize)]| {
+        for pair in corners.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            for row in &mut grid[y1.min(y2)..=y1.max(y2)] {
+                for cell in &mut row[x1.min(x2)..=x1.max(x2)] {
+                    *cell = b'.';
+                }
+            }
+        }
+    };
+    // how far a trail may stray into the half block next to it
+    let depth = |rng: &mut Rng, at: &[usize], i: usize| match i {
+        0 => 0,
+        _ => rng.range_usize(0..=(at[i] - at[i - 1]) / 2 - 2),
+    };
+
+    let (last_x, last_y) = (xs[size - 1], ys[size - 1]);
+    carve(&[(1, 0), (1, ys[0])]);
+    carve(&[(last_x, last_y), (last_x, h - 1)]);
+    let mut slopes = vec![];
+    for (j, &y) in ys.iter().enumerate() {
+        for (i, &x) in xs.iter().enumerate() {
+            if let Some(&next) = xs.get(i + 1) {
+                let d = depth(rng, &ys, j);
+                let (a, b) =
+                    (x + 2, rng.range_usize(x + 4..=x + (next - x) / 2));
+                carve(&[(x, y), (a, y), (a, y - d), (b, y - d), (b, y)]);
+                carve(&[(b, y), (next, y)]);
+                slopes.extend([(x + 1, y, b'>'), (next - 1, y, b'>')]);
+            }
+            if let Some(&next) = ys.get(j + 1) {
+                let d = depth(rng, &xs, i);
+                let (a, b) =
+                    (y + 2, rng.range_usize(y + 4..=y + (next - y) / 2));
+                carve(&[(x, y), (x, a), (x - d, a), (x - d, b), (x, b)]);
+                carve(&[(x, b), (x, next)]);
+                slopes.extend([(x, y + 1, b'v'), (x, next - 1, b'v')]);
+            }
+        }
+    }
+    for (x, y, slope) in slopes {
+        grid[y][x] = slope;
+    }
+    grid.into_iter()
+        .map(|row| String::from_utf8(row).unwrap() + "\n")
+        .collect()
+}
+
+// every hailstone is at the rock's position at a distinct time
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let rock =
+        [(); 3].map(|_| rng.range(200_000_000_000_000..=400_000_000_000_000));
+    let speed = [(); 3].map(|_| rng.range(-250..=250));
+    let mut times = HashSet::new();
+    let mut s = String::new();
+    while times.len() < size {
+        let t = rng.range(1_000_000..=1_000_000_000_000);
+        let v = [(); 3].map(|_| rng.range(-250..=250));
+        if v == speed || !times.insert(t) {
+            continue;
+        }
+        let p = [0, 1, 2].map(|i| rock[i] + (speed[i] - v[i]) * t);
+        writeln!(
+            s,
+            "{}, {}, {} @ {}, {}, {}",
+            p[0], p[1], p[2], v[0], v[1], v[2]
+        )
+        .unwrap();
+    }
+    s
+}
+
+// two groups that each stay connected after cutting any three wires, and
+// three wires between them
+fn day25(rng: &mut Rng, size: usize) -> String {
+    let mut used = HashSet::new();
+    let mut groups = vec![];
+    let mut wires = HashSet::new();
+    for _ in 0..2 {
+        let group: Vec<String> =
+            (0..size).map(|_| name(rng, &mut used, 3)).collect();
+        // every component is wired to the next two around a ring
+        for i in 0..size {
+            for j in [1, 2] {
+                wires.insert((
+                    i + groups.len() * size,
+                    (i + j) % size + groups.len() * size,
+                ));
+            }
+        }
+        for _ in 0..size / 2 {
+            let (a, b) = (rng.below(size), rng.below(size));
+            if a != b {
+                wires.insert((
+                    a + groups.len() * size,
+                    b + groups.len() * size,
+                ));
+            }
+        }
+        groups.push(group);
+    }
+    let names: Vec<String> = groups.concat();
+    let (mut a, mut b): (Vec<usize>, Vec<usize>) =
+        ((0..size).collect(), (size..2 * size).collect());
+    rng.shuffle(&mut a);
+    rng.shuffle(&mut b);
+    for i in 0..3 {
+        wires.insert((a[i], b[i]));
+    }
+
+    let mut wires: Vec<(usize, usize)> = wires.into_iter().collect();
+    wires.sort();
+    let mut lists: HashMap<usize, Vec<&str>> = HashMap::new();
+    for (a, b) in wires {
+        let (a, b) = if rng.chance(0.5) { (a, b) } else { (b, a) };
+        lists.entry(a).or_default().push(&names[b]);
+    }
+    let mut lists: Vec<(usize, Vec<&str>)> = lists.into_iter().collect();
+    lists.sort();
+    rng.shuffle(&mut lists);
+    let mut s = String::new();
+    for (a, list) in lists {
+        writeln!(s, "{}: {}", names[a], list.join(" ")).unwrap();
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_part, Answer, Part};
+
+    #[test]
+    fn deterministic() {
+        let mut rng = Rng::new(7);
+        let v: Vec<usize> = (0..1000).map(|_| rng.below(6)).collect();
+        assert!((0..6).all(|n| v.contains(&n)));
+        assert!((0..100).all(|_| (-3..=3).contains(&rng.range(-3..=3))));
+
+        let day12 = generator(12).unwrap();
+        let input = day12.generate(20, 1).unwrap();
+        assert_eq!(day12.generate(20, 1).unwrap(), input);
+        assert_ne!(day12.generate(20, 2).unwrap(), input);
+        assert!(day12.generate(21, 1).is_none());
+        assert!(generator(26).is_none());
+    }
+
+    #[test]
+    fn every_day() {
+        for day in 1..=25 {
+            let generator = generator(day).unwrap();
+            let sizes = [*generator.sizes.start(), generator.default_size];
+            for (seed, size) in sizes.into_iter().enumerate() {
+                let input = generator.generate(size, seed as u64).unwrap();
+                for part in Part::ALL {
+                    let answer = run_part(day, part, &input);
+                    assert!(answer.is_ok(), "day {} size {}", day, size);
+                }
+            }
+        }
+        let input = generator(25).unwrap().generate(10, 3).unwrap();
+        assert_eq!(
+            run_part(25, Part::One, &input).unwrap(),
+            Answer::from(100u32)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// the four directions on a grid whose y axis points south
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir {
+    E,
+    S,
+    W,
+    N,
+}
+
+impl Dir {
+    // clockwise from east
+    pub const ALL: [Dir; 4] = [Dir::E, Dir::S, Dir::W, Dir::N];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn turn_right(self) -> Self {
+        Dir::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Dir::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Dir::ALL[(self.index() + 2) % 4]
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Dir::E | Dir::W)
+    }
+
+    // the unit step in this direction
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Dir::E => (1, 0),
+            Dir::S => (0, 1),
+            Dir::W => (-1, 0),
+            Dir::N => (0, -1),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+
+    // compass letters, R/D/L/U and arrows
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'E' | 'R' | '>' => Some(Dir::E),
+            'S' | 'D' | 'v' => Some(Dir::S),
+            'W' | 'L' | '<' => Some(Dir::W),
+            'N' | 'U' | '^' => Some(Dir::N),
+            _ => None,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + From<i8>> Point2<T> {
+    pub fn step(self, dir: Dir) -> Self {
+        self + dir.delta()
+    }
+
+    // the points east, south, west and north
+    pub fn neighbours4(self) -> [Self; 4] {
+        Dir::ALL.map(|dir| self.step(dir))
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T>> Point2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T>> Point3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x)
+            + distance(self.y, other.y)
+            + distance(self.z, other.z)
+    }
+}
+
+// |a - b| without a signed type
+fn distance<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+macro_rules! impl_ops {
+    ($point:ident, $($field:ident),+) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $point { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $point { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $point { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+    };
+}
+
+impl_ops!(Point2, x, y);
+impl_ops!(Point3, x, y, z);
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dirs() {
+        assert_eq!(Dir::E.turn_left(), Dir::N);
+        assert_eq!(Dir::N.turn_right(), Dir::E);
+        assert_eq!(Dir::S.reverse(), Dir::N);
+        for dir in Dir::ALL {
+            assert_eq!(dir.turn_left().turn_left(), dir.reverse());
+            assert_eq!(
+                dir.delta::<i32>() + dir.reverse().delta(),
+                Point2::default()
+            );
+        }
+        assert_eq!(Dir::from_char('U'), Some(Dir::N));
+        assert_eq!(Dir::from_char('v'), Some(Dir::S));
+        assert_eq!(Dir::from_char('x'), None);
+        assert!(Dir::W.is_horizontal() && !Dir::N.is_horizontal());
+    }
+
+    #[test]
+    fn points() {
+        let p = Point2::new(3i64, -2);
+        assert_eq!(p.step(Dir::N), Point2::new(3, -3));
+        assert_eq!(p + Dir::E.delta() * 5, Point2::new(8, -2));
+        assert_eq!(p.manhattan(Point2::new(0, 0)), 5);
+        assert_eq!(p.neighbours4()[1], Point2::new(3, -1));
+        assert_eq!(-p, Point2::new(-3, 2));
+
+        let mut q = Point3::new(1u32, 2, 3);
+        q += Point3::new(1, 1, 1);
+        assert_eq!(q, Point3::new(2, 3, 4));
+        assert_eq!(q.manhattan(Point3::new(5, 0, 4)), 6);
+        assert_eq!(q.to_string(), "(2, 3, 4)");
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::answers::{self, Expected};
+use crate::error::AocError;
+use crate::solver::{Answer, Part};
+
+// the result of comparing the answer of a part with its known answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass(Answer),
+    Fail { answer: Answer, expected: Answer },
+    // no answer is known for the part
+    Skip,
+    Error(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Fail { .. } | Outcome::Error(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass(answer) => write!(f, "PASS    {}", answer),
+            Outcome::Fail { answer, expected } => {
+                write!(f, "FAIL    {:<20}  {}", answer, expected)
+            }
+            Outcome::Skip => write!(f, "SKIP"),
+            Outcome::Error(e) => write!(f, "ERROR   {}", e),
+        }
+    }
+}
+
+// a part is only solved when its answer is known
+pub fn verify(
+    day: u8,
+    part: Part,
+    input: &Result<String, AocError>,
+    answers: &Result<Expected, AocError>,
+) -> Outcome {
+    let (input, expected) = match (input, answers) {
+        (Err(e), _) | (_, Err(e)) => return Outcome::Error(e.to_string()),
+        (Ok(input), Ok(answers)) => match answers.get(part) {
+            Some(expected) => (input, expected),
+            None => return Outcome::Skip,
+        },
+    };
+    match crate::run_part(day, part, input) {
+        Ok(answer) if answer == *expected => Outcome::Pass(answer),
+        Ok(answer) => Outcome::Fail {
+            answer,
+            expected: expected.clone(),
+        },
+        Err(e) => Outcome::Error(e.to_string()),
+    }
+}
+
+// one part of one input with a known answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub day: u8,
+    pub name: String,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+// the day and name of every NN-name.txt in `answers_dir`, in order
+pub fn answer_files(
+    answers_dir: &Path,
+) -> Result<Vec<(u8, String)>, AocError> {
+    let entries = fs::read_dir(answers_dir)
+        .map_err(|e| AocError::from_io(answers_dir.into(), e))?;
+    let mut files = vec![];
+    for entry in entries {
+        let entry =
+            entry.map_err(|e| AocError::from_io(answers_dir.into(), e))?;
+        let file_name = entry.file_name();
+        let Some(stem) =
+            file_name.to_str().and_then(|s| s.strip_suffix(".txt"))
+        else {
+            continue;
+        };
+        let Some((day, name)) = stem.split_once('-') else {
+            continue;
+        };
+        if let Ok(day) = day.parse() {
+            files.push((day, name.to_string()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// verifies every input of `input_dir` that has an answers file in
+// `answers_dir`, for every part the answers file declares
+pub fn verify_all(
+    input_dir: &Path,
+    answers_dir: &Path,
+) -> Result<Vec<Case>, AocError> {
+    let mut cases = vec![];
+    for (day, name) in answer_files(answers_dir)? {
+        let answers = answers::try_read_answers(answers_dir, day, &name);
+        let input = crate::try_read_from(input_dir, day, &name);
+        for part in Part::ALL {
+            let outcome = verify(day, part, &input, &answers);
+            if outcome != Outcome::Skip {
+                cases.push(Case {
+                    day,
+                    name: name.clone(),
+                    part,
+                    outcome,
+                });
+            }
+        }
+    }
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_golden(examples: bool) {
+        let cases =
+            verify_all(Path::new("inputs"), Path::new(answers::ANSWERS_DIR))
+                .unwrap();
+        let cases: Vec<&Case> = cases
+            .iter()
+            .filter(|case| case.name.starts_with("example") == examples)
+            .collect();
+        assert!(!cases.is_empty());
+        let failures: Vec<String> = cases
+            .iter()
+            .filter(|case| case.outcome.is_failure())
+            .map(|case| {
+                format!(
+                    "day {} {} part {}: {}",
+                    case.day, case.name, case.part, case.outcome
+                )
+            })
+            .collect();
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn examples() {
+        assert_golden(true);
+    }
+
+    #[test]
+    fn inputs() {
+        assert_golden(false);
+    }
+
+    #[test]
+    fn files() {
+        let files = answer_files(Path::new(answers::ANSWERS_DIR)).unwrap();
+        assert!(files.contains(&(1, "input".to_string())));
+        assert!(files.contains(&(10, "example-4".to_string())));
+        assert!(answer_files(Path::new("/nonexistent")).is_err());
+    }
+
+    #[test]
+    fn outcomes() {
+        let input = Ok(crate::read_example(2));
+        let answers = Ok("one: 8\ntwo: 2287\n".parse().unwrap());
+        assert_eq!(
+            verify(2, Part::One, &input, &answers),
+            Outcome::Pass(Answer::Unsigned(8))
+        );
+        let outcome = verify(2, Part::Two, &input, &answers);
+        assert!(outcome.is_failure());
+        assert_eq!(outcome.to_string().split_whitespace().count(), 3);
+
+        let answers = Ok("one: 8\n".parse().unwrap());
+        assert_eq!(verify(2, Part::Two, &input, &answers), Outcome::Skip);
+        let input = Ok("Game 1: 3 purple".to_string());
+        assert!(matches!(
+            verify(2, Part::One, &input, &answers),
+            Outcome::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::parse::{rectangle, Line};
+
+// E, S, W, N
+const DIRS4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+// E, SE, S, SW, W, NW, N, NE
+const DIRS8: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+// a rectangle of cells stored row by row, indexed by (x, y)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cells do not fit the grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // every character of the input is mapped by `f`, which returns the
+    // reason for an error on a character it does not accept
+    pub fn parse_with<F>(input: &str, f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, &'static str>,
+    {
+        Grid::from_lines(&rectangle(input)?, f)
+    }
+
+    pub(crate) fn from_lines<F>(
+        rows: &[Line],
+        mut f: F,
+    ) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, &'static str>,
+    {
+        let Some(first) = rows.first() else {
+            return Err(ParseError::new(1, 1, "", "empty input"));
+        };
+        let width = first.text.chars().count();
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            if row.text.chars().count() != width {
+                return Err(row.error(row.text, "rows differ in width"));
+            }
+            for (i, ch) in row.text.char_indices() {
+                cells.push(f(ch).map_err(|e| row.error(&row.text[i..], e))?);
+            }
+        }
+        Ok(Grid::from_vec(width, rows.len(), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // accepts signed coordinates, which are out of bounds when negative
+    pub fn get<I: TryInto<usize>>(&self, x: I, y: I) -> Option<&T> {
+        let i = self.offset(x, y)?;
+        Some(&self.cells[i])
+    }
+
+    pub fn get_mut<I: TryInto<usize>>(
+        &mut self,
+        x: I,
+        y: I,
+    ) -> Option<&mut T> {
+        let i = self.offset(x, y)?;
+        Some(&mut self.cells[i])
+    }
+
+    pub fn contains<I: TryInto<usize>>(&self, x: I, y: I) -> bool {
+        self.offset(x, y).is_some()
+    }
+
+    fn offset<I: TryInto<usize>>(&self, x: I, y: I) -> Option<usize> {
+        let (x, y) = (x.try_into().ok()?, y.try_into().ok()?);
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find(&self, f: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.find_all(f).next()
+    }
+
+    pub fn find_all<'a>(
+        &'a self,
+        f: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| f(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    // the neighbours east, south, west and north that are in the grid
+    pub fn neighbours4(
+        &self,
+        (x, y): (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &DIRS4)
+    }
+
+    // the neighbours4 and the diagonal ones
+    pub fn neighbours8(
+        &self,
+        (x, y): (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &DIRS8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        dirs: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        dirs.iter()
+            .map(move |&(dx, dy)| {
+                (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy))
+            })
+            .filter(|&(x, y)| x < self.width && y < self.height)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(
+            self.width,
+            self.height,
+            self.cells.iter().map(f).collect(),
+        )
+    }
+
+    // rows become columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid::from_vec(self.height, self.width, cells)
+    }
+
+    // a quarter turn clockwise, the first column becomes the first row
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| {
+                (0..self.height).rev().map(move |y| self[(x, y)].clone())
+            })
+            .collect();
+        Grid::from_vec(self.height, self.width, cells)
+    }
+
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid::from_vec(self.height, self.width, cells)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) out of bounds",
+            x,
+            y
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) out of bounds",
+            x,
+            y
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, Ok)
+    }
+}
+
+// one line per row, without separators between the cells
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let grid: Grid<char> = "\nab\ncd\nef\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get(1, 0), Some(&'b'));
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+
+        let err = Grid::parse_with("12\n3x\n", |c| {
+            c.to_digit(10).ok_or("not a digit")
+        })
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.reason, "not a digit");
+        assert!("ab\nc\n".parse::<Grid<char>>().is_err());
+        assert!("".parse::<Grid<char>>().is_err());
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 2, 0);
+        let v: Vec<_> = grid.neighbours4((0, 0)).collect();
+        assert_eq!(v, [(1, 0), (0, 1)]);
+        let v: Vec<_> = grid.neighbours8((1, 0)).collect();
+        assert_eq!(v, [(2, 0), (2, 1), (1, 1), (0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn transform() {
+        let grid: Grid<char> = "abc\ndef\n".parse().unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        let v: String = grid.column(2).collect();
+        assert_eq!(v, "cf");
+        assert_eq!(grid.find(|&c| c == 'e'), Some((1, 1)));
+        assert_eq!(
+            grid.map(|c| c.is_ascii_uppercase()).get(0, 0),
+            Some(&false)
+        );
+    }
+}
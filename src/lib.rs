@@ -1,5 +1,13 @@
+use std::env;
 use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod crosscheck;
+pub mod cycle;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -25,15 +33,217 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod error;
+pub mod fetch;
+pub mod gen;
+pub mod geom;
+pub mod golden;
+pub mod grid;
+pub mod math;
+pub mod params;
+mod parse;
+pub mod pool;
+pub mod profile;
+#[cfg(test)]
+mod prop;
+pub mod report;
+pub mod search;
+pub mod solver;
+pub mod visualize;
+
+pub use error::{AocError, ParseError};
+pub use params::{Param, Params};
+pub use solver::{Answer, Part, Puzzle, Solver};
+
+macro_rules! puzzle {
+    ($mod:ident, $day:expr, $title:expr) => {
+        puzzle!($mod, $day, $title, parse_input)
+    };
+    ($mod:ident, $day:expr, $title:expr, $($parse:tt)+) => {
+        Puzzle::new(
+            $day,
+            $title,
+            |input| $mod::$($parse)+(input).map(drop),
+            |input| Ok($mod::try_part_one(input)?.into()),
+            |input| Ok($mod::try_part_two(input)?.into()),
+        )
+    };
+}
+
+// a puzzle with a `Params` struct, each part is either its
+// `try_part_*_with` or `_` when it uses none of the params
+macro_rules! tunable {
+    ($mod:ident, $day:expr, $title:expr, $one:tt, $two:tt
+     $(, $($parse:tt)+)?) => {
+        puzzle!($mod, $day, $title $(, $($parse)+)?).with_params(
+            <$mod::Params as Params>::KEYS,
+            tunable!(@part $mod, $day, $one, try_part_one),
+            tunable!(@part $mod, $day, $two, try_part_two),
+        )
+    };
+    (@part $mod:ident, $day:expr, _, $plain:ident) => {
+        |input, params| {
+            params::build::<$mod::Params>(params)
+                .map_err(|e| e.into_error($day))?;
+            Ok($mod::$plain(input).map_err(|e| e.into_error($day))?.into())
+        }
+    };
+    (@part $mod:ident, $day:expr, $with:ident, $plain:ident) => {
+        |input, params| {
+            let params = params::build::<$mod::Params>(params)
+                .map_err(|e| e.into_error($day))?;
+            Ok($mod::$with(input, &params)
+                .map_err(|e| e.into_error($day))?
+                .into())
+        }
+    };
+}
+
+static PUZZLES: [Puzzle; 25] = [
+    puzzle!(day01, 1, "Trebuchet?!"),
+    tunable!(day02, 2, "Cube Conundrum", try_part_one_with, _),
+    puzzle!(day03, 3, "Gear Ratios"),
+    puzzle!(day04, 4, "Scratchcards"),
+    puzzle!(day05, 5, "If You Give A Seed A Fertilizer"),
+    puzzle!(day06, 6, "Wait For It"),
+    puzzle!(day07, 7, "Camel Cards"),
+    puzzle!(day08, 8, "Haunted Wasteland"),
+    puzzle!(day09, 9, "Mirage Maintenance"),
+    puzzle!(day10, 10, "Pipe Maze"),
+    tunable!(
+        day11,
+        11,
+        "Cosmic Expansion",
+        try_part_one_with,
+        try_part_two_with
+    ),
+    puzzle!(day12, 12, "Hot Springs"),
+    puzzle!(day13, 13, "Point of Incidence"),
+    tunable!(day14, 14, "Parabolic Reflector Dish", _, try_part_two_with),
+    puzzle!(day15, 15, "Lens Library"),
+    puzzle!(day16, 16, "The Floor Will Be Lava"),
+    puzzle!(day17, 17, "Clumsy Crucible"),
+    puzzle!(day18, 18, "Lavaduct Lagoon"),
+    puzzle!(day19, 19, "Aplenty"),
+    tunable!(day20, 20, "Pulse Propagation", try_part_one_with, _),
+    tunable!(
+        day21,
+        21,
+        "Step Counter",
+        try_part_one_with,
+        try_part_two_with
+    ),
+    puzzle!(day22, 22, "Sand Slabs"),
+    puzzle!(day23, 23, "A Long Walk"),
+    tunable!(
+        day24,
+        24,
+        "Never Tell Me The Odds",
+        try_part_one_with,
+        _,
+        parse_input::<f64>
+    ),
+    puzzle!(day25, 25, "Snowverload"),
+];
+
+pub fn solvers() -> impl Iterator<Item = &'static dyn Solver> {
+    PUZZLES.iter().map(|puzzle| puzzle as &dyn Solver)
+}
+
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    solvers().find(|solver| solver.day() == day)
+}
+
+pub fn run_part(
+    day: u8,
+    part: Part,
+    input: &str,
+) -> Result<Answer, AocError> {
+    run_part_with(day, part, input, &[])
+}
+
+pub fn run_part_with(
+    day: u8,
+    part: Part,
+    input: &str,
+    params: &[Param],
+) -> Result<Answer, AocError> {
+    let solver = solver(day).ok_or(AocError::UnknownDay(day))?;
+    solver.try_solve_with(part, input, params)
+}
+
+// overrides the default `inputs` directory of the `read_*` helpers
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from("inputs"),
+    }
+}
+
+pub fn input_path(dir: &Path, day: u8, filename: &str) -> PathBuf {
+    dir.join(format!("{:02}-{}.txt", day, filename))
+}
+
+pub fn try_read_file(path: &Path) -> Result<String, AocError> {
+    fs::read_to_string(path).map_err(|e| AocError::from_io(path.into(), e))
+}
+
+pub fn try_read_stdin() -> Result<String, AocError> {
+    let mut input = String::new();
+    match io::stdin().read_to_string(&mut input) {
+        Ok(_) => Ok(input),
+        Err(e) => Err(AocError::from_io(PathBuf::from("-"), e)),
+    }
+}
+
+pub fn try_read_from(
+    dir: &Path,
+    day: u8,
+    filename: &str,
+) -> Result<String, AocError> {
+    try_read_file(&input_path(dir, day, filename))
+}
+
+pub fn try_read_as_string(
+    day: u8,
+    filename: &str,
+) -> Result<String, AocError> {
+    try_read_from(&input_dir(), day, filename)
+}
+
+pub fn try_read_input(day: u8) -> Result<String, AocError> {
+    try_read_as_string(day, "input")
+}
+
+pub fn try_read_example(day: u8) -> Result<String, AocError> {
+    try_read_example_n(day, 1)
+}
+
+// the first example is NN-example.txt, the others NN-example-N.txt
+pub fn example_name(n: usize) -> String {
+    match n {
+        1 => "example".to_string(),
+        n => format!("example-{}", n),
+    }
+}
+
+pub fn try_read_example_n(day: u8, n: usize) -> Result<String, AocError> {
+    try_read_as_string(day, &example_name(n))
+}
 
 pub fn read_as_string(day: u8, filename: &str) -> String {
-    let filename = format!("inputs/{:02}-{}.txt", day, filename);
-    fs::read_to_string(filename).unwrap()
+    try_read_as_string(day, filename).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn read_input(day: u8) -> String {
     read_as_string(day, "input")
 }
 pub fn read_example(day: u8) -> String {
-    read_as_string(day, "example")
+    read_example_n(day, 1)
+}
+
+pub fn read_example_n(day: u8, n: usize) -> String {
+    read_as_string(day, &example_name(n))
 }
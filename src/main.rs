@@ -1,79 +1,517 @@
 use std::env;
-use std::fmt::Display;
-use std::time::SystemTime;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant, SystemTime};
 
-fn main() {
-    macro_rules! puzzle {
-        ($mod:ident, $title:expr) => {
-            (
-                $title,
-                |input| Box::new(aoc::$mod::part_one(input)),
-                |input| Box::new(aoc::$mod::part_two(input)),
-            )
+use aoc::bench::{self, Stats};
+use aoc::cli::{self, Command, Options};
+use aoc::crosscheck;
+use aoc::fetch::{self, Client, Config, Fetched};
+use aoc::gen::{self, Generator};
+use aoc::golden::{self, Outcome};
+use aoc::profile::{self, Span};
+use aoc::report::{self, Format, Record, Status};
+use aoc::visualize;
+use aoc::{pool, AocError, Param, Part};
+
+// the spans of the part are only recorded with `profile`
+fn solve(
+    day: u8,
+    part: Part,
+    input: &str,
+    params: &[Param],
+    profile: bool,
+) -> (Record, Vec<Span>) {
+    let t0 = Instant::now();
+    let solve = || aoc::run_part_with(day, part, input, params);
+    let (answer, spans) = match profile {
+        true => profile::record(solve),
+        false => (solve(), vec![]),
+    };
+    let duration = t0.elapsed();
+    let title = aoc::solver(day).unwrap().title();
+    let record = match answer {
+        Ok(answer) => Record {
+            day,
+            part,
+            title,
+            answer: Some(answer),
+            duration,
+            status: Status::Ok,
+        },
+        Err(e) => Record {
+            day,
+            part,
+            title,
+            answer: None,
+            duration,
+            status: Status::Error(e.to_string()),
+        },
+    };
+    (record, spans)
+}
+
+// the spans of every part that has any, with their share of the part
+fn profile_report(records: &[Record], spans: &[Vec<Span>]) -> String {
+    let mut s = format!(
+        "Day  Part  {:<16}  {:>6}  {:>12}  {:>6}\n",
+        "Span", "Calls", "Total", "Share"
+    );
+    for (record, spans) in records.iter().zip(spans) {
+        for line in profile::render(spans, record.duration).lines() {
+            s.push_str(&format!(
+                "{:3}  {:<4}  {}\n",
+                record.day,
+                record.part.to_string(),
+                line
+            ));
+        }
+    }
+    if spans.iter().all(|spans| spans.is_empty()) {
+        s.push_str("none of the parts has spans\n");
+    }
+    s
+}
+
+type Task<'a> = (u8, Part, &'a Result<String, AocError>, &'a [Param]);
+
+// returns false if any input is missing or malformed; the parts are
+// solved on `options.jobs` threads but reported in order
+fn run(options: &Options, show_time: bool) -> bool {
+    let text = options.format == Format::Text;
+    let parts: Vec<Vec<Part>> = options
+        .days
+        .iter()
+        .map(|&day| options.parts_for(day))
+        .collect();
+    let inputs: Vec<_> = options
+        .days
+        .iter()
+        .map(|&day| options.read_input(day))
+        .collect();
+    let params: Vec<Vec<Param>> = options
+        .days
+        .iter()
+        .map(|&day| options.params_for(day))
+        .collect();
+    let tasks: Vec<Task> = options
+        .days
+        .iter()
+        .zip(inputs.iter().zip(parts.iter()))
+        .zip(params.iter())
+        .flat_map(|((&day, (input, parts)), params)| {
+            parts
+                .iter()
+                .map(move |&part| (day, part, input, &params[..]))
+        })
+        .collect();
+
+    let mut records = vec![];
+    let mut spans = vec![];
+    let mut durations = vec![];
+    let failed = |day: u8, part: Part, e: String| {
+        let record = Record {
+            day,
+            part,
+            title: aoc::solver(day).unwrap().title(),
+            answer: None,
+            duration: Duration::ZERO,
+            status: Status::Error(e),
+        };
+        (record, vec![])
+    };
+    // a solver that panics fails its part only, the other parts go on
+    let solve_task = |(day, part, input, params): Task| match input {
+        Ok(input) => panic::catch_unwind(AssertUnwindSafe(|| {
+            solve(day, part, input, params, options.profile)
+        }))
+        .unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            failed(day, part, format!("panicked: {}", message))
+        }),
+        Err(e) => failed(day, part, e.to_string()),
+    };
+    let each = |(record, part_spans): (Record, Vec<Span>)| {
+        let day = record.day;
+        let index = options.days.iter().position(|&d| d == day).unwrap();
+        let parts = &parts[index];
+        let (first, last) = (parts[0], parts[parts.len() - 1]);
+        if text && record.part == first {
+            println!("--- Day {}: {} ---", day, record.title);
+        }
+        match (text, &inputs[index]) {
+            (false, _) => (),
+            (true, Err(e)) if record.part == first => {
+                eprintln!("Error: {}", e);
+                println!();
+            }
+            (true, Err(_)) => (),
+            (true, Ok(_)) => {
+                match (&record.answer, &record.status) {
+                    (Some(answer), _) => {
+                        println!("Part {}: {}", record.part, answer);
+                        durations.push(record.duration);
+                    }
+                    (None, Status::Error(e)) => eprintln!("Error: {}", e),
+                    (None, Status::Ok) => unreachable!(),
+                }
+                if record.part == last {
+                    if show_time {
+                        let durations: Vec<String> = durations
+                            .iter()
+                            .map(|d| format!("{:?}", d))
+                            .collect();
+                        println!("Duration: ({})", durations.join(", "));
+                    }
+                    durations.clear();
+                    println!();
+                }
+            }
+        }
+        records.push(record);
+        spans.push(part_spans);
+    };
+    pool::map_ordered(options.jobs, tasks, solve_task, each);
+    if let Some(report) = report::render(&records, options.format) {
+        print!("{}", report);
+    }
+    // after the answers, on stderr when they are json or csv
+    if options.profile {
+        let report = profile_report(&records, &spans);
+        match text {
+            true => print!("{}", report),
+            false => eprint!("{}", report),
+        }
+    }
+    records.iter().all(|r| r.status == Status::Ok)
+}
+
+// the solve durations exclude the median parse duration, which every
+// part pays again
+fn bench(options: &Options) -> bool {
+    let mut ok = true;
+    println!(
+        "{} warmup and {} measured runs per part, the parts exclude the \
+         median parse duration",
+        options.warmup, options.runs
+    );
+    println!();
+    for &day in options.days.iter() {
+        let solver = aoc::solver(day).unwrap();
+        println!("--- Day {}: {} ---", day, solver.title());
+        let input = match options.read_input(day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                println!();
+                ok = false;
+                continue;
+            }
         };
+
+        let (warmup, runs) = (options.warmup, options.runs);
+        let parse = bench::sample(warmup, runs, || solver.try_parse(&input));
+        let parse = match parse {
+            Ok(samples) => Stats::new(&samples).unwrap(),
+            Err(e) => {
+                eprintln!("Error: {}", e.into_error(day));
+                println!();
+                ok = false;
+                continue;
+            }
+        };
+        println!(
+            "{:<8}  {:>12}  {:>12}  {:>12}  {:>12}",
+            "", "min", "median", "mean", "stddev"
+        );
+        println!("{:<8}  {}", "Parse", parse);
+        let params = options.params_for(day);
+        for part in options.parts_for(day) {
+            let samples = bench::sample(warmup, runs, || {
+                solver.try_solve_with(part, &input, &params)
+            });
+            match samples {
+                Ok(samples) => {
+                    let solve = Stats::new(&samples).unwrap();
+                    let name = format!("Part {}", part);
+                    println!("{:<8}  {}", name, solve.minus(parse.median));
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    ok = false;
+                }
+            }
+        }
+        println!();
     }
+    ok
+}
 
-    type SolverFn = fn(&str) -> Box<dyn Display>;
-
-    let puzzles: Vec<(&str, SolverFn, SolverFn)> = vec![
-        puzzle!(day01, "Trebuchet?!"),
-        puzzle!(day02, "Cube Conundrum"),
-        puzzle!(day03, "Gear Ratios"),
-        puzzle!(day04, "Scratchcards"),
-        puzzle!(day05, "If You Give A Seed A Fertilizer"),
-        puzzle!(day06, "Wait For It"),
-        puzzle!(day07, "Camel Cards"),
-        puzzle!(day08, "Haunted Wasteland"),
-        puzzle!(day09, "Mirage Maintenance"),
-        puzzle!(day10, "Pipe Maze"),
-        puzzle!(day11, "Cosmic Expansion"),
-        puzzle!(day12, "Hot Springs"),
-        puzzle!(day13, "Point of Incidence"),
-        puzzle!(day14, "Parabolic Reflector Dish"),
-        puzzle!(day15, "Lens Library"),
-        puzzle!(day16, "The Floor Will Be Lava"),
-        puzzle!(day17, "Clumsy Crucible"),
-        puzzle!(day18, "Lavaduct Lagoon"),
-        puzzle!(day19, "Aplenty"),
-        puzzle!(day20, "Pulse Propagation"),
-        puzzle!(day21, "Step Counter"),
-        puzzle!(day22, "Sand Slabs"),
-        puzzle!(day23, "A Long Walk"),
-        puzzle!(day24, "Never Tell Me The Odds"),
-        puzzle!(day25, "Snowverload"),
-    ];
-
-    let filename = match env::args().find(|a| a == "--example") {
-        None => "input",
-        Some(_) => "example",
+// prints a PASS/FAIL table, returns false if any answer is wrong or
+// cannot be computed; parts without a known answer are skipped
+fn check(options: &Options) -> bool {
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    println!("Day  Part  Result  {:<20}  Expected", "Answer");
+    for &day in options.days.iter() {
+        let input = options.read_input(day);
+        let answers = options.read_answers(day);
+        for part in options.parts() {
+            let outcome = golden::verify(day, part, &input, &answers);
+            match outcome {
+                Outcome::Pass(_) => passed += 1,
+                Outcome::Skip => skipped += 1,
+                _ => failed += 1,
+            }
+            println!("{:3}  {:<4}  {}", day, part.to_string(), outcome);
+        }
+    }
+    println!();
+    println!("{} passed, {} failed, {} skipped", passed, failed, skipped);
+    failed == 0
+}
+
+// downloads the missing inputs, returns false if any download failed
+fn fetch(options: &Options) -> bool {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return false;
+        }
+    };
+    let client = || {
+        let mut config = config.clone();
+        if let Some(url) = &options.base_url {
+            config.base_url = Some(url.clone());
+        }
+        Client::from_config(&config)
     };
+    let dir = options.input_dir();
+    let mut ok = true;
+    for &day in options.days.iter() {
+        match fetch::fetch_input(&dir, day, client) {
+            Ok(Fetched::Cached(path)) => {
+                println!("Day {:2}: {} is cached", day, path.display())
+            }
+            Ok(Fetched::Downloaded(path, bytes)) => println!(
+                "Day {:2}: downloaded {} bytes to {}",
+                day,
+                bytes,
+                path.display()
+            ),
+            Err(e) => {
+                eprintln!("Day {:2}: Error: {}", day, e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
 
-    let show_time = env::args().any(|a| a == "--time");
+// --seed or one from the clock
+fn seed(options: &Options) -> u64 {
+    options.seed.unwrap_or_else(|| {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
+        now.map_or(0, |d| d.as_nanos() as u64)
+    })
+}
 
-    let mut days: Vec<usize> =
-        env::args().filter_map(|a| a.parse().ok()).collect();
+fn size_error(day: u8, generator: &Generator) -> String {
+    format!(
+        "the size of day {} is the number of {}, from {} to {}",
+        day,
+        generator.size,
+        generator.sizes.start(),
+        generator.sizes.end()
+    )
+}
 
-    if days.is_empty() {
-        days = (1..=puzzles.len()).collect();
+// prints a random input of the day, and the size and seed that make it
+// again to stderr
+fn generate(options: &Options) -> bool {
+    let day = options.days[0];
+    let Some(generator) = gen::generator(day) else {
+        eprintln!("Error: day {} has no generator", day);
+        return false;
+    };
+    let size = options.size.unwrap_or(generator.default_size);
+    let seed = seed(options);
+    match generator.generate(size, seed) {
+        Some(input) => {
+            eprintln!(
+                "Day {}: {} {}, seed {}",
+                day, size, generator.size, seed
+            );
+            print!("{}", input);
+            true
+        }
+        None => {
+            eprintln!("Error: {}", size_error(day, &generator));
+            false
+        }
     }
+}
 
-    for day in days {
-        let (title, part1, part2) = &puzzles[day - 1];
-        let input = aoc::read_as_string(day as u8, filename);
-        let input = input.as_str();
+// compares the selected parts with their references on the example or
+// file input, or else on `options.runs` generated inputs with the seeds
+// from --seed on; the parts without a reference are left out
+fn crosscheck(options: &Options) -> bool {
+    let given = options.example.is_some() || options.file.is_some();
+    let seed = seed(options);
+    let (mut passed, mut failed) = (0, 0);
+    println!(
+        "Day  Part  {:<25}  Result  {:<20}  Reference",
+        "Input", "Answer"
+    );
+    for &day in options.days.iter() {
+        let parts = options.parts_for(day);
+        for reference in crosscheck::references(day)
+            .filter(|reference| parts.contains(&reference.part))
+        {
+            let generator = gen::generator(day).unwrap();
+            let size = options.size.unwrap_or(reference.size);
+            let inputs: Vec<(String, Result<String, String>)> = match given {
+                true => vec![(
+                    options.input_name(),
+                    options.read_input(day).map_err(|e| e.to_string()),
+                )],
+                false => (0..options.runs as u64)
+                    .map(|i| seed.wrapping_add(i))
+                    .map(|seed| {
+                        let input = generator.generate(size, seed);
+                        (
+                            format!("seed {}", seed),
+                            input.ok_or_else(|| size_error(day, &generator)),
+                        )
+                    })
+                    .collect(),
+            };
+            let params = options.params_for(day);
+            for (name, input) in inputs {
+                let outcome = match input {
+                    Ok(input) => {
+                        crosscheck::compare(reference, &input, &params)
+                    }
+                    Err(e) => Outcome::Error(e),
+                };
+                match outcome.is_failure() {
+                    true => failed += 1,
+                    false => passed += 1,
+                }
+                println!(
+                    "{:3}  {:<4}  {:<25}  {}",
+                    day,
+                    reference.part.to_string(),
+                    name,
+                    outcome
+                );
+            }
+        }
+    }
+    println!();
+    println!("{} passed, {} failed", passed, failed);
+    failed == 0
+}
 
-        println!("--- Day {}: {} ---", day, title);
-        let t0 = SystemTime::now();
-        println!("Part One: {}", part1(input));
-        let t1 = SystemTime::now();
-        println!("Part Two: {}", part2(input));
-        let t2 = SystemTime::now();
+const VISUALIZE_DIR: &str = "visualizations";
 
-        if show_time {
-            let d1 = t1.duration_since(t0).unwrap_or_default();
-            let d2 = t2.duration_since(t1).unwrap_or_default();
-            println!("Duration: {:?}", (d1, d2));
+// draws the selected days on the terminal or into image files, returns
+// false if any of them cannot be drawn
+fn draw(options: &Options, format: visualize::Format) -> bool {
+    let mut ok = true;
+    for &day in options.days.iter() {
+        let Some(visualize) = visualize::visualizer(day) else {
+            eprintln!("Day {:2}: Error: there is no visualization", day);
+            ok = false;
+            continue;
+        };
+        let tiles = options.read_input(day).and_then(|input| {
+            visualize(&input).map_err(|e| e.into_error(day))
+        });
+        let tiles = match tiles {
+            Ok(tiles) => tiles,
+            Err(e) => {
+                eprintln!("Day {:2}: Error: {}", day, e);
+                ok = false;
+                continue;
+            }
+        };
+        let image = visualize::render(&tiles, format);
+        if format == visualize::Format::Ansi {
+            let title = aoc::solver(day).unwrap().title();
+            println!("--- Day {}: {} ---", day, title);
+            io::stdout().write_all(&image).unwrap();
+            println!();
+            continue;
         }
-        println!();
+        let name = format!(
+            "{:02}-{}.{}",
+            day,
+            options.input_name(),
+            format.extension()
+        );
+        let path = Path::new(VISUALIZE_DIR).join(name);
+        match fs::create_dir_all(VISUALIZE_DIR)
+            .and_then(|_| fs::write(&path, image))
+        {
+            Ok(()) => println!("Day {:2}: wrote {}", day, path.display()),
+            Err(e) => {
+                eprintln!("Day {:2}: Error: {}: {}", day, path.display(), e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+fn main() {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Try `aoc --help` for more information.");
+            process::exit(2);
+        }
+    };
+
+    let ok = match command {
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            true
+        }
+        Command::List => {
+            for solver in aoc::solvers() {
+                match solver.params() {
+                    [] => println!("{:2}  {}", solver.day(), solver.title()),
+                    params => println!(
+                        "{:2}  {:<32}  {}",
+                        solver.day(),
+                        solver.title(),
+                        params.join(", ")
+                    ),
+                }
+            }
+            true
+        }
+        Command::Run(options) => match options.visualize {
+            Some(format) => draw(&options, format),
+            None => run(&options, options.time),
+        },
+        Command::Bench(options) => bench(&options),
+        Command::Check(options) => check(&options),
+        Command::Fetch(options) => fetch(&options),
+        Command::Generate(options) => generate(&options),
+        Command::Crosscheck(options) => crosscheck(&options),
+    };
+
+    if !ok {
+        process::exit(1);
     }
 }
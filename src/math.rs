@@ -0,0 +1,123 @@
+// number theory for the puzzles whose answer is where cycles line up, every
+// function returns none instead of overflowing
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+// the lcm of every value, 1 for none
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+// returns (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = egcd(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+// the coefficients are bounded by the inputs
+fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+// Chinese remainder theorem for congruences x = r (mod m), the moduli need
+// not be coprime. Returns (x, lcm of the moduli) with the least x >= 0, or
+// none when the congruences contradict each other.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut m): (i128, i128) = (0, 1);
+    for &(r, n) in congruences {
+        if n == 0 {
+            return None;
+        }
+        let (r, n) = (r as i128 % n as i128, n as i128);
+        // x + m * k = r (mod n) has a solution when gcd(m, n) divides r - x
+        let (g, p, _) = egcd(m, n);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let k = ((r - x) / g)
+            .rem_euclid(step)
+            .checked_mul(p.rem_euclid(step))?
+            % step;
+        x = x.checked_add(m.checked_mul(k)?)?;
+        m = m.checked_mul(step)?;
+        if m > u64::MAX as i128 {
+            return None;
+        }
+        x = x.rem_euclid(m);
+    }
+    Some((x as u64, m as u64))
+}
+
+// The first time t that every cycle (offset, period) is on, that is t is
+// at least the offset and a multiple of the period after it.
+pub fn first_common(cycles: &[(u64, u64)]) -> Option<u64> {
+    let (x, m) = crt(cycles)?;
+    let start = cycles.iter().map(|&(offset, _)| offset).max()?;
+    if x >= start {
+        return Some(x);
+    }
+    (start - x).div_ceil(m).checked_mul(m)?.checked_add(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm_all([2, 3, 4]), Some(12));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        let (g, x, y) = extended_gcd(-4, 6);
+        assert_eq!((g, -4 * x + 6 * y), (2, 2));
+    }
+
+    #[test]
+    fn remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(0, u64::MAX), (0, u64::MAX - 1)]), None);
+
+        // periods that start from zero meet at the lcm
+        assert_eq!(first_common(&[(2, 2), (3, 3)]), Some(6));
+        // offsets shift the meeting point
+        assert_eq!(first_common(&[(1, 4), (3, 6)]), Some(9));
+        assert_eq!(first_common(&[(13, 4), (3, 6)]), Some(21));
+        assert_eq!(first_common(&[(1, 4), (2, 6)]), None);
+    }
+}
@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AocError;
+
+// a `key=value` setting given with --param
+pub type Param = (String, String);
+
+// The constants of a day that are not in its input, with the values of
+// the puzzle as the default.
+pub trait Params: Default {
+    // the keys `set` accepts
+    const KEYS: &'static [&'static str];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamError {
+    pub key: String,
+    pub reason: String,
+}
+
+impl ParamError {
+    pub fn new(key: &str, reason: &str) -> Self {
+        ParamError {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn unknown(key: &str) -> Self {
+        ParamError::new(key, "unknown parameter")
+    }
+
+    pub fn into_error(self, day: u8) -> AocError {
+        AocError::InvalidParam {
+            day,
+            key: self.key,
+            reason: self.reason,
+        }
+    }
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.reason)
+    }
+}
+
+impl Error for ParamError {}
+
+// the days without params reject every key
+impl Params for () {
+    const KEYS: &'static [&'static str] = &[];
+
+    fn set(&mut self, key: &str, _: &str) -> Result<(), ParamError> {
+        Err(ParamError::unknown(key))
+    }
+}
+
+// "key=value" => ("key", "value")
+pub fn parse_param(s: &str) -> Option<Param> {
+    let (key, value) = s.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+pub fn value<T: FromStr>(key: &str, value: &str) -> Result<T, ParamError> {
+    value.parse().map_err(|_| {
+        ParamError::new(key, &format!("invalid value {:?}", value))
+    })
+}
+
+// the defaults with every param set in order
+pub fn build<P: Params>(params: &[Param]) -> Result<P, ParamError> {
+    let mut p = P::default();
+    for (key, value) in params {
+        p.set(key, value)?;
+    }
+    Ok(p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Bag {
+        red: u32,
+    }
+
+    impl Params for Bag {
+        const KEYS: &'static [&'static str] = &["red"];
+
+        fn set(&mut self, key: &str, v: &str) -> Result<(), ParamError> {
+            match key {
+                "red" => self.red = value(key, v)?,
+                _ => return Err(ParamError::unknown(key)),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(parse_param("red = 3"), Some(("red".into(), "3".into())));
+        assert_eq!(parse_param("red"), None);
+        assert_eq!(parse_param("=3"), None);
+
+        let set = |s: &str| build::<Bag>(&[parse_param(s).unwrap()]);
+        assert_eq!(set("red=3"), Ok(Bag { red: 3 }));
+        assert_eq!(build::<Bag>(&[]), Ok(Bag::default()));
+        let err = set("red=x").unwrap_err();
+        assert_eq!(err.to_string(), "red: invalid value \"x\"");
+        assert_eq!(set("blue=1"), Err(ParamError::unknown("blue")));
+        assert!(build::<()>(&[("red".into(), "3".into())]).is_err());
+        assert_eq!(
+            ParamError::unknown("blue").into_error(2).to_string(),
+            "invalid parameter blue for day 2: unknown parameter"
+        );
+    }
+}
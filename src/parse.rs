@@ -0,0 +1,146 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+// a line of the puzzle input together with its 1-based line number
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    // `at` should be a slice of `self.text` so that the column can be
+    // derived from it, otherwise the error points at the first column
+    pub fn error(&self, at: &str, reason: &str) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize).wrapping_sub(start);
+        let column = if offset <= self.text.len() {
+            offset + 1
+        } else {
+            1
+        };
+        ParseError::new(self.number, column, self.text, reason)
+    }
+
+    pub fn parse<T: FromStr>(&self, s: &str) -> Result<T, ParseError> {
+        s.parse().map_err(|_| self.error(s, "invalid value"))
+    }
+
+    pub fn split_once(
+        &self,
+        s: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        s.split_once(delimiter).ok_or_else(|| {
+            self.error(s, &format!("expected {:?}", delimiter))
+        })
+    }
+
+    pub fn get(&self, range: Range<usize>) -> Result<&'a str, ParseError> {
+        let start = self.text.len().min(range.start);
+        self.text.get(range).ok_or_else(|| {
+            self.error(
+                self.text.get(start..).unwrap_or(""),
+                "line is too short",
+            )
+        })
+    }
+}
+
+// the lines of the input with leading and trailing blank lines skipped,
+// numbered as in the original input
+pub(crate) fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    let all: Vec<Line> = input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+        .collect();
+    let first = all.iter().position(|line| !line.text.trim().is_empty());
+    let last = all.iter().rposition(|line| !line.text.trim().is_empty());
+    let range = match (first, last) {
+        (Some(first), Some(last)) => first..last + 1,
+        _ => 0..0,
+    };
+    all.into_iter().take(range.end).skip(range.start)
+}
+
+// the lines of a rectangular grid, all of the same width
+pub(crate) fn rectangle(input: &str) -> Result<Vec<Line<'_>>, ParseError> {
+    let rows: Vec<Line> = lines(input).collect();
+    let Some(first) = rows.first() else {
+        return Err(ParseError::new(1, 1, "", "empty input"));
+    };
+    let width = first.text.len();
+    match rows.iter().find(|row| row.text.len() != width) {
+        Some(row) => Err(row.error(row.text, "rows differ in width")),
+        None => Ok(rows),
+    }
+}
+
+// groups of lines separated by blank lines
+pub(crate) fn paragraphs(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut paragraphs: Vec<Vec<Line>> = vec![];
+    let mut paragraph: Vec<Line> = vec![];
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph);
+                paragraph = vec![];
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbered_lines() {
+        let input = "\n\n  a\nb\n\nc\n\n";
+        let v: Vec<(usize, &str)> =
+            lines(input).map(|line| (line.number, line.text)).collect();
+        assert_eq!(v, [(3, "  a"), (4, "b"), (5, ""), (6, "c")]);
+        assert_eq!(lines("\n \n").count(), 0);
+
+        let v: Vec<Vec<usize>> = paragraphs(input)
+            .iter()
+            .map(|p| p.iter().map(|line| line.number).collect())
+            .collect();
+        assert_eq!(v, [vec![3, 4], vec![6]]);
+
+        assert_eq!(rectangle("ab\ncd\n").unwrap().len(), 2);
+        assert_eq!(rectangle("ab\nc\n").unwrap_err().line, 2);
+        assert!(rectangle("\n").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let line = Line {
+            number: 7,
+            text: "Game 7: 3 red, x blue",
+        };
+        let (_, cubes) = line.split_once(line.text, ": ").unwrap();
+        let err = line.parse::<u32>(&cubes[7..8]).unwrap_err();
+        assert_eq!((err.line, err.column), (7, 16));
+        assert_eq!(err.text, line.text);
+
+        let err = line.split_once(cubes, ";").unwrap_err();
+        assert_eq!((err.column, err.reason.as_str()), (9, "expected \";\""));
+
+        assert_eq!(line.get(0..4), Ok("Game"));
+        assert_eq!(line.get(20..24).unwrap_err().column, 21);
+        assert_eq!(line.error("elsewhere", "oops").column, 1);
+    }
+}
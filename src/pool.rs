@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+// applies `f` to every item on `jobs` threads and hands the results to
+// `each` in the order of the items, as soon as all earlier results are in
+pub fn map_ordered<T, R, F>(
+    jobs: usize,
+    items: Vec<T>,
+    f: F,
+    mut each: impl FnMut(R),
+) where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, count.max(1)) {
+            let tx = tx.clone();
+            let (queue, f) = (&queue, &f);
+            scope.spawn(move || loop {
+                let Some((i, item)) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                if tx.send((i, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in rx {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                each(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut results = vec![];
+        map_ordered(
+            4,
+            items,
+            |i| {
+                // later items finish first
+                thread::sleep(Duration::from_millis(20 - i));
+                i * i
+            },
+            |r| results.push(r),
+        );
+        assert_eq!(results, (0..20).map(|i| i * i).collect::<Vec<_>>());
+
+        let mut results = vec![];
+        map_ordered(0, vec![1, 2], |i| i + 1, |r| results.push(r));
+        assert_eq!(results, [2, 3]);
+        map_ordered(3, Vec::<u8>::new(), |i| i, |_| panic!());
+    }
+}
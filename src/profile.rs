@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+// the time spent in the spans of one name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub name: &'static str,
+    pub calls: usize,
+    pub total: Duration,
+}
+
+thread_local! {
+    // the spans of the `record` running on this thread, if any
+    static SPANS: RefCell<Option<Vec<Span>>> = const { RefCell::new(None) };
+}
+
+// ends its span when dropped
+pub struct Guard {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+// Starts a span that lasts until the guard is dropped. Outside of a
+// `record` it only checks that nothing is recorded. The spans of a name
+// add up, so a recursive function is timed where it is called instead.
+pub fn span(name: &'static str) -> Guard {
+    let recording = SPANS.with(|spans| spans.borrow().is_some());
+    Guard {
+        name,
+        start: recording.then(Instant::now),
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        SPANS.with(|spans| {
+            let mut spans = spans.borrow_mut();
+            let Some(spans) = spans.as_mut() else {
+                return;
+            };
+            match spans.iter_mut().find(|span| span.name == self.name) {
+                Some(span) => {
+                    span.calls += 1;
+                    span.total += elapsed;
+                }
+                None => spans.push(Span {
+                    name: self.name,
+                    calls: 1,
+                    total: elapsed,
+                }),
+            }
+        });
+    }
+}
+
+// f as a span
+pub fn time<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let _span = span(name);
+    f()
+}
+
+// runs f and returns the spans it ended on this thread, in the order
+// they first ended
+pub fn record<T>(f: impl FnOnce() -> T) -> (T, Vec<Span>) {
+    let outer = SPANS.with(|spans| spans.replace(Some(vec![])));
+    let value = f();
+    let spans = SPANS.with(|spans| spans.replace(outer));
+    (value, spans.unwrap_or_default())
+}
+
+// a line per span with its share of `whole`
+pub fn render(spans: &[Span], whole: Duration) -> String {
+    let mut s = String::new();
+    for span in spans {
+        let share = match whole.is_zero() {
+            true => 0.0,
+            false => 100.0 * span.total.as_secs_f64() / whole.as_secs_f64(),
+        };
+        writeln!(
+            s,
+            "{:<16}  {:>6}  {:>12}  {:>5.1}%",
+            span.name,
+            span.calls,
+            format!("{:.1?}", span.total),
+            share
+        )
+        .unwrap();
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans() {
+        // nothing is recorded outside of `record`
+        assert_eq!(time("parse", || 1), 1);
+        let (_, spans) = record(|| ());
+        assert!(spans.is_empty());
+
+        let (value, spans) = record(|| {
+            let _outer = span("solve");
+            let n: u32 = (0..3).map(|i| time("step", || i)).sum();
+            let (_, inner) = record(|| time("inner", || ()));
+            assert_eq!(inner.len(), 1);
+            n
+        });
+        assert_eq!(value, 3);
+        let names: Vec<_> = spans.iter().map(|s| (s.name, s.calls)).collect();
+        assert_eq!(names, [("step", 3), ("solve", 1)]);
+        assert!(spans[1].total >= spans[0].total);
+
+        let spans = [Span {
+            name: "dfs",
+            calls: 2,
+            total: Duration::from_millis(30),
+        }];
+        let text = render(&spans, Duration::from_millis(40));
+        assert_eq!(text, "dfs                    2        30.0ms   75.0%\n");
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::gen::Rng;
+
+// the values each property is checked on
+pub const CASES: usize = 256;
+// the most times a failing value is replaced with a simpler one
+const SHRINKS: usize = 1000;
+
+// Makes random values and, once one breaks a property, simpler values
+// to try instead.
+pub trait Strategy {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    // values a bit simpler than `value`, the simplest first
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value>;
+}
+
+// an integer in the range, shrinking towards the start
+macro_rules! integers {
+    ($($t:ty),+) => {
+        $(impl Strategy for RangeInclusive<$t> {
+            type Value = $t;
+
+            fn generate(&self, rng: &mut Rng) -> $t {
+                rng.range(*self.start() as i64..=*self.end() as i64) as $t
+            }
+
+            fn shrink(&self, &value: &$t) -> Vec<$t> {
+                let mut smaller = vec![];
+                let mut distance = value - *self.start();
+                while distance > 0 {
+                    smaller.push(value - distance);
+                    distance /= 2;
+                }
+                smaller
+            }
+        })+
+    };
+}
+
+integers!(u8, i32, i64, usize);
+
+// one value of each strategy, shrinking one at a time
+macro_rules! tuples {
+    ($(($($s:ident $i:tt),+))+) => {
+        $(impl<$($s: Strategy),+> Strategy for ($($s,)+) {
+            type Value = ($($s::Value,)+);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$i.generate(rng),)+)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut smaller = vec![];
+                $(for x in self.$i.shrink(&value.$i) {
+                    let mut v = value.clone();
+                    v.$i = x;
+                    smaller.push(v);
+                })+
+                smaller
+            }
+        })+
+    };
+}
+
+tuples! {
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+}
+
+pub struct VecOf<S> {
+    element: S,
+    len: RangeInclusive<usize>,
+}
+
+// a vector with a length in `len`, shrinking to fewer and then to
+// simpler elements
+pub fn vec<S: Strategy>(element: S, len: RangeInclusive<usize>) -> VecOf<S> {
+    VecOf { element, len }
+}
+
+impl<S: Strategy> Strategy for VecOf<S> {
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let n = rng.range_usize(self.len.clone());
+        (0..n).map(|_| self.element.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut smaller = vec![];
+        let (min, n) = (*self.len.start(), value.len());
+        if n > min {
+            let half = (n / 2).max(min);
+            if half + 1 < n {
+                smaller.push(value[..half].to_vec());
+                smaller.push(value[n - half..].to_vec());
+            }
+            for i in 0..n {
+                let mut v = value.clone();
+                v.remove(i);
+                smaller.push(v);
+            }
+        }
+        for (i, x) in value.iter().enumerate() {
+            for y in self.element.shrink(x) {
+                let mut v = value.clone();
+                v[i] = y;
+                smaller.push(v);
+            }
+        }
+        smaller
+    }
+}
+
+pub struct OneOf<T: 'static>(&'static [T]);
+
+// one of the items, shrinking towards the first
+pub fn one_of<T: Clone + Debug + PartialEq>(items: &'static [T]) -> OneOf<T> {
+    OneOf(items)
+}
+
+impl<T: Clone + Debug + PartialEq> Strategy for OneOf<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        rng.pick(self.0).clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let i = self.0.iter().position(|x| x == value).unwrap_or(0);
+        self.0[..i].to_vec()
+    }
+}
+
+// a panic in the property counts as a failure
+fn holds<T>(property: &impl Fn(&T) -> bool, value: &T) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(|| property(value))).unwrap_or(false)
+}
+
+// the first of CASES values that breaks the property, shrunk, with the
+// value it was shrunk from
+pub fn falsify<S: Strategy>(
+    strategy: &S,
+    property: impl Fn(&S::Value) -> bool,
+) -> Option<(S::Value, S::Value)> {
+    let mut rng = Rng::new(0);
+    let original = (0..CASES)
+        .map(|_| strategy.generate(&mut rng))
+        .find(|value| !holds(&property, value))?;
+
+    let mut value = original.clone();
+    for _ in 0..SHRINKS {
+        match strategy
+            .shrink(&value)
+            .into_iter()
+            .find(|smaller| !holds(&property, smaller))
+        {
+            Some(smaller) => value = smaller,
+            None => break,
+        }
+    }
+    Some((value, original))
+}
+
+pub fn check<S: Strategy>(strategy: S, property: impl Fn(&S::Value) -> bool) {
+    if let Some((value, original)) = falsify(&strategy, property) {
+        panic!(
+            "property does not hold for {:?} (shrunk from {:?})",
+            value, original
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinking() {
+        assert_eq!((3..=10).shrink(&10), [3, 7, 9]);
+        assert!((3..=10).shrink(&3).is_empty());
+        assert_eq!(one_of(b".#?").shrink(&b'?'), b".#");
+
+        // a sum is shrunk to the smallest one that fails
+        let strategy = vec(0..=1000i64, 0..=10);
+        let (value, original) =
+            falsify(&strategy, |v| v.iter().sum::<i64>() < 100).unwrap();
+        assert_eq!(value.iter().sum::<i64>(), 100, "{:?}", original);
+        assert!(value.len() <= original.len());
+
+        let (value, _) = falsify(&(0..=50usize, 0..=50usize), |&(a, b)| {
+            assert!(a < 10 || b < 10);
+            true
+        })
+        .unwrap();
+        assert_eq!(value, (10, 10));
+        assert!(falsify(&(0..=9u8), |&x| x < 10).is_none());
+        check(vec(one_of(&[1, 2]), 1..=3), |v| !v.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::solver::{Answer, Part};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("invalid format {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error(String),
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Error(_) => "error",
+        }
+    }
+}
+
+// the outcome of solving one part of one day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub title: &'static str,
+    pub answer: Option<Answer>,
+    pub duration: Duration,
+    pub status: Status,
+}
+
+impl Record {
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        write!(
+            s,
+            "{{\"day\":{},\"part\":{},\"title\":{},\"answer\":{},\
+             \"duration_ns\":{},\"status\":{}",
+            self.day,
+            part_number(self.part),
+            json_string(self.title),
+            self.answer.as_ref().map_or("null".into(), Answer::to_json),
+            self.duration.as_nanos(),
+            json_string(self.status.as_str()),
+        )
+        .unwrap();
+        if let Status::Error(e) = &self.status {
+            write!(s, ",\"error\":{}", json_string(e)).unwrap();
+        }
+        s.push('}');
+        s
+    }
+
+    pub fn to_csv(&self) -> String {
+        let error = match &self.status {
+            Status::Ok => "",
+            Status::Error(e) => e,
+        };
+        [
+            self.day.to_string(),
+            part_number(self.part).to_string(),
+            csv_field(self.title),
+            csv_field(
+                &self
+                    .answer
+                    .as_ref()
+                    .map(Answer::to_string)
+                    .unwrap_or_default(),
+            ),
+            self.duration.as_nanos().to_string(),
+            self.status.as_str().to_string(),
+            csv_field(error),
+        ]
+        .join(",")
+    }
+}
+
+pub const CSV_HEADER: &str = "day,part,title,answer,duration_ns,status,error";
+
+// renders the records as a JSON array or as CSV with a header line, text
+// is printed while solving so there is nothing to render
+pub fn render(records: &[Record], format: Format) -> Option<String> {
+    match format {
+        Format::Text => None,
+        Format::Json => {
+            let records: Vec<String> = records
+                .iter()
+                .map(|r| format!("  {}", r.to_json()))
+                .collect();
+            match records.is_empty() {
+                true => Some("[]\n".to_string()),
+                false => Some(format!("[\n{}\n]\n", records.join(",\n"))),
+            }
+        }
+        Format::Csv => {
+            let mut s = format!("{}\n", CSV_HEADER);
+            for record in records {
+                s.push_str(&record.to_csv());
+                s.push('\n');
+            }
+            Some(s)
+        }
+    }
+}
+
+fn part_number(part: Part) -> u8 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => {
+                write!(json, "\\u{:04x}", ch as u32).unwrap()
+            }
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: Part::One,
+                title: "Trebuchet?!",
+                answer: Some(Answer::Unsigned(142)),
+                duration: Duration::from_micros(81),
+                status: Status::Ok,
+            },
+            Record {
+                day: 1,
+                part: Part::Two,
+                title: "Trebuchet?!",
+                answer: None,
+                duration: Duration::ZERO,
+                status: Status::Error("input \"x\", y\nz".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            render(&records(), Format::Json).unwrap(),
+            "[\n  \
+             {\"day\":1,\"part\":1,\"title\":\"Trebuchet?!\",\
+             \"answer\":142,\"duration_ns\":81000,\"status\":\"ok\"},\n  \
+             {\"day\":1,\"part\":2,\"title\":\"Trebuchet?!\",\
+             \"answer\":null,\"duration_ns\":0,\"status\":\"error\",\
+             \"error\":\"input \\\"x\\\", y\\nz\"}\n\
+             ]\n"
+        );
+        assert_eq!(render(&[], Format::Json).unwrap(), "[]\n");
+        assert_eq!(render(&records(), Format::Text), None);
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            render(&records(), Format::Csv).unwrap(),
+            "day,part,title,answer,duration_ns,status,error\n\
+             1,1,Trebuchet?!,142,81000,ok,\n\
+             1,2,Trebuchet?!,,0,error,\"input \"\"x\"\", y\nz\"\n"
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+// the nodes from the start to a goal, both included, and the cost of the
+// edges between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+// every node seen so far with the index of the node it was reached from
+struct Tree<N, C> {
+    nodes: Vec<(N, usize, C)>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Tree<N, C> {
+    fn new(start: N, cost: C) -> Self {
+        Tree {
+            nodes: vec![(start.clone(), usize::MAX, cost)],
+            index: HashMap::from([(start, 0)]),
+        }
+    }
+
+    fn path(&self, mut i: usize) -> Path<N, C> {
+        let cost = self.nodes[i].2;
+        let mut nodes = vec![];
+        while i != usize::MAX {
+            nodes.push(self.nodes[i].0.clone());
+            i = self.nodes[i].1;
+        }
+        nodes.reverse();
+        Path { cost, nodes }
+    }
+}
+
+// Breadth first order from the start with the depth of every node, each
+// node is expanded only when it is yielded, so the graph may be infinite.
+pub struct Bfs<N, F> {
+    tree: Tree<N, usize>,
+    neighbours: F,
+    next: usize,
+    last: usize,
+}
+
+impl<N, I, F> Iterator for Bfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    type Item = (N, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.next;
+        let (node, _, depth) = self.tree.nodes.get(i)?.clone();
+        for next in (self.neighbours)(&node) {
+            if let Entry::Vacant(e) = self.tree.index.entry(next) {
+                self.tree.nodes.push((e.key().clone(), i, depth + 1));
+                e.insert(self.tree.nodes.len() - 1);
+            }
+        }
+        self.next += 1;
+        self.last = i;
+        Some((node, depth))
+    }
+}
+
+impl<N: Clone + Eq + Hash, F> Bfs<N, F> {
+    // the path to the node yielded last
+    pub fn path(&self) -> Path<N, usize> {
+        self.tree.path(self.last)
+    }
+}
+
+pub fn bfs_iter<N, I, F>(start: N, neighbours: F) -> Bfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    Bfs {
+        tree: Tree::new(start, 0),
+        neighbours,
+        next: 0,
+        last: 0,
+    }
+}
+
+// the shortest path to a goal when every edge costs 1
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = bfs_iter(start, neighbours);
+    while let Some((node, _)) = search.next() {
+        if is_goal(&node) {
+            return Some(search.path());
+        }
+    }
+    None
+}
+
+// the cheapest path to a goal, the neighbours come with the cost of the
+// edge to them
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+// dijkstra guided by a heuristic, which must never overestimate the cost
+// to a goal and must not drop by more than the cost of an edge
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut tree = Tree::new(start, C::default());
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > tree.nodes[i].2 {
+            // reached for less since it was queued
+            continue;
+        }
+        let node = tree.nodes[i].0.clone();
+        if is_goal(&node) {
+            return Some(tree.path(i));
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            let j = match tree.index.entry(next) {
+                Entry::Vacant(e) => {
+                    tree.nodes.push((e.key().clone(), i, next_cost));
+                    *e.insert(tree.nodes.len() - 1)
+                }
+                Entry::Occupied(e) => {
+                    let j = *e.get();
+                    if next_cost >= tree.nodes[j].2 {
+                        continue;
+                    }
+                    tree.nodes[j].1 = i;
+                    tree.nodes[j].2 = next_cost;
+                    j
+                }
+            };
+            let estimate = next_cost + heuristic(&tree.nodes[j].0);
+            queue.push(Reverse((estimate, next_cost, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Point2;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+..#....
+.##.##.
+...#...
+.#...#.
+";
+
+    fn open(grid: &Grid<char>, pos: &Point2<i32>) -> Vec<Point2<i32>> {
+        pos.neighbours4()
+            .into_iter()
+            .filter(|p| grid.get(p.x, p.y) == Some(&'.'))
+            .collect()
+    }
+
+    #[test]
+    fn breadth_first() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = Point2::new(0, 0);
+        let end = Point2::new(6, 0);
+        let path = bfs(start, |p| open(&grid, p), |p| *p == end).unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.len(), 13);
+        assert_eq!((path.nodes[0], path.nodes[12]), (start, end));
+        assert!(path.nodes.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+        assert_eq!(bfs(start, |p| open(&grid, p), |_| false), None);
+
+        // an infinite line is searched lazily
+        let v: Vec<(i32, usize)> = bfs_iter(0, |&x| [x - 1, x + 1])
+            .take_while(|&(_, depth)| depth <= 1)
+            .collect();
+        assert_eq!(v, [(0, 0), (-1, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn cheapest() {
+        // the top row and the middle are expensive to enter
+        let cost = |(x, y)| match (x, y) {
+            (1, 1) => 10,
+            (_, 0) => 5,
+            _ => 1,
+        };
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x <= 2 && y <= 2)
+                .map(move |p| (p, cost(p)))
+        };
+        let goal = |p: &(i32, i32)| *p == (2, 2);
+        let around = [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)];
+        let path = dijkstra((0, 0), neighbours, goal).unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 4,
+                nodes: around.to_vec()
+            }
+        );
+
+        let heuristic = |&(x, y): &(i32, i32)| (2 - x) + (2 - y);
+        let path = astar((0, 0), neighbours, heuristic, goal).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(dijkstra((0, 0), neighbours, |_| false), None);
+    }
+}
@@ -0,0 +1,344 @@
+use std::convert::Infallible;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::{AocError, ParseError};
+use crate::params::{self, Param};
+
+// the answer of a part; numbers compare by value whatever their variant
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i64),
+    Unsigned(u64),
+    Str(String),
+}
+
+impl Answer {
+    fn as_number(&self) -> Option<i128> {
+        match self {
+            Answer::Int(n) => Some(*n as i128),
+            Answer::Unsigned(n) => Some(*n as i128),
+            Answer::Str(_) => None,
+        }
+    }
+
+    // a JSON number, or a JSON string for a text answer
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Str(s) => crate::report::json_string(s),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Str(a), Answer::Str(b)) => a == b,
+            _ => self
+                .as_number()
+                .is_some_and(|a| Some(a) == other.as_number()),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => n.fmt(f),
+            Answer::Unsigned(n) => n.fmt(f),
+            Answer::Str(s) => f.pad(s),
+        }
+    }
+}
+
+// "-5" is an Int, "5" is Unsigned and anything else a Str
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = s.parse() {
+            Ok(Answer::Unsigned(n))
+        } else if let Ok(n) = s.parse() {
+            Ok(Answer::Int(n))
+        } else {
+            Ok(Answer::Str(s.to_string()))
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $as:ty, $($t:ty),+) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::$variant(n as $as)
+            }
+        })+
+    };
+}
+
+answer_from!(Int, i64, i32, i64);
+answer_from!(Unsigned, u64, u16, u32, u64, usize);
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "One"),
+            Part::Two => write!(f, "Two"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" | "one" | "One" => Ok(Part::One),
+            "2" | "two" | "Two" => Ok(Part::Two),
+            _ => Err(format!("invalid part {:?}, expected one or two", s)),
+        }
+    }
+}
+
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    // only parses the input, so that parsing can be timed on its own
+    fn try_parse(&self, input: &str) -> Result<(), ParseError>;
+    fn try_part_one(&self, input: &str) -> Result<Answer, ParseError>;
+    fn try_part_two(&self, input: &str) -> Result<Answer, ParseError>;
+
+    fn part_one(&self, input: &str) -> Answer {
+        self.try_part_one(input).unwrap()
+    }
+
+    fn part_two(&self, input: &str) -> Answer {
+        self.try_part_two(input).unwrap()
+    }
+
+    fn try_solve(
+        &self,
+        part: Part,
+        input: &str,
+    ) -> Result<Answer, ParseError> {
+        match part {
+            Part::One => self.try_part_one(input),
+            Part::Two => self.try_part_two(input),
+        }
+    }
+
+    fn solve(&self, part: Part, input: &str) -> Answer {
+        self.try_solve(part, input).unwrap()
+    }
+
+    // the keys of the params of the day, none for most days
+    fn params(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // solves a part with some of the params of the day changed
+    fn try_solve_with(
+        &self,
+        part: Part,
+        input: &str,
+        params: &[Param],
+    ) -> Result<Answer, AocError> {
+        params::build::<()>(params).map_err(|e| e.into_error(self.day()))?;
+        self.try_solve(part, input)
+            .map_err(|e| e.into_error(self.day()))
+    }
+}
+
+type SolverFn = fn(&str) -> Result<Answer, ParseError>;
+type ParseFn = fn(&str) -> Result<(), ParseError>;
+type SolverWithFn = fn(&str, &[Param]) -> Result<Answer, AocError>;
+
+// a solver backed by the plain `part_one` / `part_two` functions of a
+// `dayNN` module
+pub struct Puzzle {
+    day: u8,
+    title: &'static str,
+    parse: ParseFn,
+    part_one: SolverFn,
+    part_two: SolverFn,
+    params: &'static [&'static str],
+    // both parts with params, for the days that have them
+    with: Option<(SolverWithFn, SolverWithFn)>,
+}
+
+impl Puzzle {
+    pub const fn new(
+        day: u8,
+        title: &'static str,
+        parse: ParseFn,
+        part_one: SolverFn,
+        part_two: SolverFn,
+    ) -> Self {
+        Puzzle {
+            day,
+            title,
+            parse,
+            part_one,
+            part_two,
+            params: &[],
+            with: None,
+        }
+    }
+
+    pub const fn with_params(
+        mut self,
+        params: &'static [&'static str],
+        part_one: SolverWithFn,
+        part_two: SolverWithFn,
+    ) -> Self {
+        self.params = params;
+        self.with = Some((part_one, part_two));
+        self
+    }
+}
+
+impl Solver for Puzzle {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn try_parse(&self, input: &str) -> Result<(), ParseError> {
+        (self.parse)(input)
+    }
+
+    fn try_part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        (self.part_one)(input)
+    }
+
+    fn try_part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        (self.part_two)(input)
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        self.params
+    }
+
+    fn try_solve_with(
+        &self,
+        part: Part,
+        input: &str,
+        params: &[Param],
+    ) -> Result<Answer, AocError> {
+        match (self.with, part) {
+            (Some((part_one, _)), Part::One) => part_one(input, params),
+            (Some((_, part_two)), Part::Two) => part_two(input, params),
+            (None, _) => {
+                params::build::<()>(params)
+                    .map_err(|e| e.into_error(self.day))?;
+                self.try_solve(part, input)
+                    .map_err(|e| e.into_error(self.day))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, Part};
+    use crate::read_example;
+    use crate::{run_part, run_part_with, solver, solvers, AocError};
+
+    #[test]
+    fn registry() {
+        let days: Vec<u8> = solvers().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<u8>>());
+        assert!(solver(0).is_none());
+        assert!(solver(26).is_none());
+
+        let day02 = solver(2).unwrap();
+        assert_eq!(day02.title(), "Cube Conundrum");
+        let input = read_example(2);
+        assert_eq!(day02.part_one(&input), Answer::Unsigned(8));
+        assert_eq!(day02.part_two(&input), 2286u32.into());
+
+        assert!(day02.try_parse(&input).is_ok());
+        let err = day02.try_parse("Game 1: 3 purple").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 11));
+        let err = day02.try_part_one("Game 1: 3 purple").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 11));
+
+        let answer = run_part(2, "two".parse().unwrap(), &input).unwrap();
+        assert_eq!(answer.to_string(), "2286");
+        assert!(matches!(
+            run_part(26, Part::One, &input),
+            Err(AocError::UnknownDay(26))
+        ));
+    }
+
+    #[test]
+    fn params() {
+        let param = |k: &str, v: &str| (k.to_string(), v.to_string());
+        let input = read_example(2);
+        assert_eq!(solver(2).unwrap().params(), ["red", "green", "blue"]);
+        let run = |part, params: &[_]| run_part_with(2, part, &input, params);
+        let answer = run(Part::One, &[param("red", "20")]).unwrap();
+        assert_eq!(answer, Answer::Unsigned(11));
+        // part two ignores the bag but not a mistake
+        assert_eq!(
+            run(Part::Two, &[param("red", "1")]).unwrap(),
+            2286u32.into()
+        );
+        let err = run(Part::Two, &[param("red", "x")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid parameter red for day 2: invalid value \"x\""
+        );
+        assert!(matches!(
+            run_part_with(1, Part::One, "1", &[param("red", "1")]),
+            Err(AocError::InvalidParam { day: 1, .. })
+        ));
+        assert!(solver(1).unwrap().params().is_empty());
+    }
+
+    #[test]
+    fn answers() {
+        assert_eq!(Answer::Int(966), Answer::Unsigned(966));
+        assert_ne!(Answer::Int(-1), Answer::Unsigned(u64::MAX));
+        assert_ne!(Answer::Unsigned(1), Answer::Str("1".to_string()));
+
+        assert_eq!("42".parse(), Ok(Answer::Unsigned(42)));
+        assert!(matches!("-42".parse(), Ok(Answer::Int(-42))));
+        assert!(matches!("fifty stars".parse(), Ok(Answer::Str(_))));
+
+        let answer = Answer::from("fifty \"stars\"");
+        assert_eq!(answer.to_string(), "fifty \"stars\"");
+        assert_eq!(answer.to_json(), "\"fifty \\\"stars\\\"\"");
+        assert_eq!(Answer::from(-3i64).to_json(), "-3");
+        assert_eq!(Answer::from(7usize).to_json(), "7");
+        assert_eq!(format!("{:<3}|", Answer::Unsigned(7)), "7  |");
+    }
+}
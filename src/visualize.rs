@@ -0,0 +1,239 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::{day10, day14, day16, day18, day21};
+
+// the pixels of a tile in an image
+pub const SCALE: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Color {
+    // the background
+    #[default]
+    Dim,
+    Plain,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Cyan,
+}
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Color::Dim => "\x1b[90m",
+            Color::Plain => "\x1b[0m",
+            Color::Red => "\x1b[91m",
+            Color::Green => "\x1b[92m",
+            Color::Yellow => "\x1b[93m",
+            Color::Blue => "\x1b[94m",
+            Color::Cyan => "\x1b[96m",
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Dim => [0x1e, 0x1e, 0x1e],
+            Color::Plain => [0xb0, 0xb0, 0xb0],
+            Color::Red => [0xe0, 0x40, 0x40],
+            Color::Green => [0x40, 0xc0, 0x40],
+            Color::Yellow => [0xf0, 0xd0, 0x30],
+            Color::Blue => [0x40, 0x70, 0xe0],
+            Color::Cyan => [0x40, 0xd0, 0xd0],
+        }
+    }
+}
+
+// a character for the terminal and a color for both
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tile {
+    pub ch: char,
+    pub color: Color,
+}
+
+impl Tile {
+    pub fn new(ch: char, color: Color) -> Self {
+        Tile { ch, color }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Ansi,
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ansi => "txt",
+            Format::Ppm => "ppm",
+            Format::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Format::Ansi),
+            "ppm" => Ok(Format::Ppm),
+            "svg" => Ok(Format::Svg),
+            _ => Err(()),
+        }
+    }
+}
+
+pub type VisualizeFn = fn(&str) -> Result<Grid<Tile>, ParseError>;
+
+pub fn visualizer(day: u8) -> Option<VisualizeFn> {
+    match day {
+        10 => Some(day10::visualize),
+        14 => Some(day14::visualize),
+        16 => Some(day16::visualize),
+        18 => Some(day18::visualize),
+        21 => Some(day21::visualize),
+        _ => None,
+    }
+}
+
+pub fn render(tiles: &Grid<Tile>, format: Format) -> Vec<u8> {
+    match format {
+        Format::Ansi => ansi(tiles).into_bytes(),
+        Format::Ppm => ppm(tiles),
+        Format::Svg => svg(tiles).into_bytes(),
+    }
+}
+
+// the escape codes only change with the color, every row ends reset
+pub fn ansi(tiles: &Grid<Tile>) -> String {
+    let mut s = String::new();
+    for row in tiles.rows() {
+        let mut color = None;
+        for tile in row {
+            if color != Some(tile.color) {
+                s.push_str(tile.color.ansi());
+                color = Some(tile.color);
+            }
+            s.push(tile.ch);
+        }
+        s.push_str(Color::Plain.ansi());
+        s.push('\n');
+    }
+    s
+}
+
+// a binary PPM with SCALE x SCALE pixels per tile
+pub fn ppm(tiles: &Grid<Tile>) -> Vec<u8> {
+    let (w, h) = (tiles.width() * SCALE, tiles.height() * SCALE);
+    let mut image = format!("P6\n{} {}\n255\n", w, h).into_bytes();
+    for row in tiles.rows() {
+        let line: Vec<u8> = row
+            .iter()
+            .flat_map(|tile| tile.color.rgb().repeat(SCALE))
+            .collect();
+        for _ in 0..SCALE {
+            image.extend_from_slice(&line);
+        }
+    }
+    image
+}
+
+// one rect per run of a color in a row, on a background of Dim
+pub fn svg(tiles: &Grid<Tile>) -> String {
+    let hex = |color: Color| {
+        let [r, g, b] = color.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    };
+    let (w, h) = (tiles.width(), tiles.height());
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" \
+         height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">",
+        w * SCALE,
+        h * SCALE,
+        w,
+        h
+    )
+    .unwrap();
+    writeln!(
+        s,
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        w,
+        h,
+        hex(Color::Dim)
+    )
+    .unwrap();
+    for (y, row) in tiles.rows().enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a.color == b.color) {
+            if run[0].color != Color::Dim {
+                writeln!(
+                    s,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" \
+                     fill=\"{}\"/>",
+                    x,
+                    y,
+                    run.len(),
+                    hex(run[0].color)
+                )
+                .unwrap();
+            }
+            x += run.len();
+        }
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiles() -> Grid<Tile> {
+        let grid: Grid<char> = "#..\n##O\n".parse().unwrap();
+        grid.map(|&c| match c {
+            '#' => Tile::new(c, Color::Plain),
+            'O' => Tile::new(c, Color::Yellow),
+            _ => Tile::new(c, Color::Dim),
+        })
+    }
+
+    #[test]
+    fn formats() {
+        let tiles = tiles();
+        let text = ansi(&tiles);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("\x1b[0m#\x1b[90m..\x1b[0m\n"));
+
+        let image = ppm(&tiles);
+        let header = format!("P6\n{} {}\n255\n", 3 * SCALE, 2 * SCALE);
+        assert!(image.starts_with(header.as_bytes()));
+        assert_eq!(image.len(), header.len() + 3 * 2 * SCALE * SCALE * 3);
+
+        let image = svg(&tiles);
+        // the background and three runs
+        assert_eq!(image.matches("<rect").count(), 4);
+        assert!(image.contains("x=\"0\" y=\"1\" width=\"2\""));
+        assert_eq!("svg".parse(), Ok(Format::Svg));
+        assert!("png".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn days() {
+        for day in [10, 14, 16, 18, 21] {
+            let input = crate::read_example(day);
+            let tiles = visualizer(day).unwrap()(&input).unwrap();
+            assert!(tiles.width() > 0, "day {}", day);
+        }
+        assert!(visualizer(1).is_none());
+    }
+}